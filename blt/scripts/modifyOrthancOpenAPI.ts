@@ -118,5 +118,31 @@ spec.paths['/blt/studies'] = {
   }
 };
 
+spec.paths['/blt/studies/{AccessionNumber}'] = {
+  delete: {
+    operationId: 'withdrawBltStudy',
+    summary: 'Withdraw BLT study',
+    description: 'Withdraw the request for a BLT study, canceling its PACS retrieve, anonymization and push jobs which are not done yet.',
+    tags: ['Custom BLT Plugin'],
+    parameters: [
+      {
+        name: 'AccessionNumber',
+        in: 'path',
+        required: true,
+        description: 'Search_AccessionNumber of the BLT study',
+        schema: { type: 'string' }
+      }
+    ],
+    responses: {
+      '204': {
+        description: 'BLT study was withdrawn.'
+      },
+      '404': {
+        description: 'BLT study was not requested.'
+      }
+    }
+  }
+};
+
 console.log(JSON.stringify(spec, undefined, 2));
 
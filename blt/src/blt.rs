@@ -9,13 +9,12 @@
 
 mod api;
 mod database;
-mod dicom_date;
 mod error;
 mod models;
 mod on_change;
 mod on_study_received;
 mod push;
 
-pub use api::route_http_request;
+pub use api::{register_error_codes, route_http_request};
 pub use database::BltDatabase;
 pub use on_change::on_change;
@@ -1,28 +1,110 @@
 use super::database::BltDatabase;
-use super::models::BltStudy;
+use super::models::{AccessionNumber, BltStudy};
 use http::StatusCode;
+use orthanc_sdk::OrthancErrorCode;
+use orthanc_sdk::api::JobsClient;
+use orthanc_sdk::api::types::{JobId, JobState};
 use orthanc_sdk::bindings::OrthancPluginContext;
-use orthanc_sdk::http::{Method, Request, Response};
+use orthanc_sdk::http::{Method, Request, Response, RestError};
+use std::sync::OnceLock;
 
-/// - On `GET`: return list of BLT studies.
-/// - On `POST`: query for the study by AccessionNumber in the first modality
+/// Error code for when a study is requested while it was already requested.
+static STUDY_ALREADY_REQUESTED: OnceLock<OrthancErrorCode> = OnceLock::new();
+
+/// Register the custom error codes of BLT.
+pub fn register_error_codes(context: *mut OrthancPluginContext) {
+    match orthanc_sdk::register_error_code(
+        context,
+        1,
+        StatusCode::CONFLICT,
+        "BLT study already requested",
+    ) {
+        Ok(code) => {
+            let _ = STUDY_ALREADY_REQUESTED.set(code);
+        }
+        Err(e) => tracing::warn!("could not register error code: {e}"),
+    }
+}
+
+/// - On `GET /blt/studies`: return list of BLT studies.
+/// - On `POST /blt/studies`: query for the study by AccessionNumber in the first modality
 ///              Orthanc is configured with. If the study is found, then
 ///              add its details to an in-memory database and start a retrieval job.
+///              If the study was already requested, respond with HTTP 409.
+/// - On `DELETE /blt/studies/{AccessionNumber}`: withdraw the request for the study, canceling its unfinished jobs.
 pub fn route_http_request(
     context: *mut OrthancPluginContext,
     req: Request<BltStudy>,
     db: &mut BltDatabase,
-) -> Response<serde_json::Value> {
-    match req.method {
-        Method::Get => Response::ok(serde_json::to_value(db.list_studies()).unwrap()),
-        Method::Post => {
+) -> Result<Response<serde_json::Value>, RestError> {
+    let accession_number = req.url.strip_prefix("/blt/studies/");
+    let res = match (req.method, accession_number) {
+        (Method::Get, None) => Response::ok(serde_json::to_value(db.list_studies()).unwrap()),
+        (Method::Post, None) => {
             if let Some(study) = req.body {
+                if db.get(&study.accession_number).is_some() {
+                    let code = STUDY_ALREADY_REQUESTED
+                        .get()
+                        .copied()
+                        .unwrap_or(OrthancErrorCode::DuplicateResource);
+                    return Err(RestError::new(code)
+                        .with_details(format!("AccessionNumber={}", study.accession_number)));
+                }
                 query_and_retrieve(context, db, study).into()
             } else {
                 Response::from(StatusCode::BAD_REQUEST)
             }
         }
+        (Method::Delete, Some(accession_number)) => {
+            match AccessionNumber::try_new(compact_str::CompactString::from(accession_number)) {
+                Ok(accession_number) => withdraw(context, db, &accession_number),
+                Err(_) => Response::from(StatusCode::BAD_REQUEST),
+            }
+        }
         _ => Response::from(StatusCode::METHOD_NOT_ALLOWED),
+    };
+    Ok(res)
+}
+
+/// Remove a study from the database, canceling its jobs which are still running.
+///
+/// Jobs which finish regardless are ignored, because the study is no longer
+/// in the database.
+fn withdraw(
+    context: *mut OrthancPluginContext,
+    db: &mut BltDatabase,
+    accession_number: &AccessionNumber,
+) -> Response<serde_json::Value> {
+    let Some(jobs) = db.remove(accession_number) else {
+        return Response::from(StatusCode::NOT_FOUND);
+    };
+    for job_id in jobs {
+        cancel_job(context, &job_id, accession_number);
+    }
+    Response::from(StatusCode::NO_CONTENT)
+}
+
+/// Cancel a job of a withdrawn study, unless it is done already.
+fn cancel_job(
+    context: *mut OrthancPluginContext,
+    job_id: &JobId,
+    accession_number: &AccessionNumber,
+) {
+    let client = JobsClient::new(context);
+    match client.get(job_id).ok_data() {
+        Ok(job) if matches!(job.state, JobState::Success | JobState::Failure) => (),
+        Ok(_) => {
+            if let Err(e) = client.cancel(job_id) {
+                e.trace();
+            } else {
+                tracing::info!(
+                    job = job_id.to_string(),
+                    AccessionNumber = accession_number.as_str(),
+                    "canceled job of withdrawn study"
+                );
+            }
+        }
+        Err(e) => tracing::error!("{e:?}"),
     }
 }
 
@@ -66,3 +148,40 @@ fn query_and_retrieve(
         })),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blt::database::tests::study;
+
+    #[test]
+    fn test_withdraw_study_without_jobs() {
+        let mut db = BltDatabase::default();
+        db.add_study_without_jobs(study("98765"));
+        let accession_number =
+            AccessionNumber::try_new(compact_str::CompactString::const_new("98765")).unwrap();
+        // no jobs to cancel, so Orthanc is not called
+        let context = std::ptr::null_mut();
+        let response = withdraw(context, &mut db, &accession_number);
+        assert_eq!(response.code, StatusCode::NO_CONTENT);
+        let response = withdraw(context, &mut db, &accession_number);
+        assert_eq!(response.code, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_route_withdraw_by_url() {
+        let mut db = BltDatabase::default();
+        db.add_study_without_jobs(study("98765"));
+        let context = std::ptr::null_mut();
+        let delete = |url| Request {
+            url,
+            body: None,
+            method: Method::Delete,
+        };
+        let response = route_http_request(context, delete("/blt/studies/98765"), &mut db);
+        assert_eq!(response.unwrap().code, StatusCode::NO_CONTENT);
+        assert!(db.list_studies().is_empty());
+        let response = route_http_request(context, delete("/blt/studies"), &mut db);
+        assert_eq!(response.unwrap().code, StatusCode::METHOD_NOT_ALLOWED);
+    }
+}
@@ -1,7 +1,7 @@
 use super::models::{AccessionNumber, BltStudy};
 use bimap::BiMap;
 use orthanc_sdk::api::types::{JobId, QueryId};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 // TODO use ValKey instead of an in-process HashMap, for persistence and scalability
 
@@ -13,6 +13,7 @@ pub struct BltDatabase {
     retrieve_jobs: BiMap<JobId, AccessionNumber>,
     anonymize_jobs: BiMap<JobId, AccessionNumber>,
     push_jobs: BiMap<JobId, AccessionNumber>,
+    resubmitted_pushes: HashSet<JobId>,
 }
 
 #[derive(serde::Serialize)]
@@ -38,6 +39,7 @@ impl BltDatabase {
             retrieve_jobs: BiMap::with_capacity(capacity),
             anonymize_jobs: BiMap::with_capacity(capacity),
             push_jobs: BiMap::with_capacity(capacity),
+            resubmitted_pushes: HashSet::new(),
         }
     }
 
@@ -110,4 +112,101 @@ impl BltDatabase {
         assert!(self.anonymize_jobs.contains_right(&accession_number));
         self.push_jobs.insert(job_id, accession_number);
     }
+
+    /// Returns `true` if the specified job ID is a BLT push job.
+    pub fn has_push(&self, id: &JobId) -> bool {
+        self.push_jobs.contains_left(id)
+    }
+
+    /// Record that a push job is being resubmitted.
+    /// Returns `false` if the job was already resubmitted before.
+    pub fn resubmit_push(&mut self, id: &JobId) -> bool {
+        self.resubmitted_pushes.insert(id.clone())
+    }
+
+    /// Remove a BLT study request.
+    ///
+    /// Returns [None] if the study was not requested, otherwise the IDs of its
+    /// retrieve, anonymization and push jobs, which may still be running.
+    pub fn remove(&mut self, accession_number: &AccessionNumber) -> Option<Vec<JobId>> {
+        self.studies.remove(accession_number)?;
+        self.queries.remove_by_right(accession_number);
+        let push_job = self.push_jobs.remove_by_right(accession_number);
+        if let Some((push_job, _)) = &push_job {
+            self.resubmitted_pushes.remove(push_job);
+        }
+        let jobs = [
+            self.retrieve_jobs.remove_by_right(accession_number),
+            self.anonymize_jobs.remove_by_right(accession_number),
+            push_job,
+        ];
+        Some(
+            jobs.into_iter()
+                .flatten()
+                .map(|(job_id, _)| job_id)
+                .collect(),
+        )
+    }
+
+    /// Add a study without any jobs.
+    #[cfg(test)]
+    pub(crate) fn add_study_without_jobs(&mut self, study: BltStudy) {
+        self.studies.insert(study.accession_number.clone(), study);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Create a [BltStudy] with the given AccessionNumber.
+    pub(crate) fn study(accession_number: &str) -> BltStudy {
+        serde_json::from_value(serde_json::json!({
+            "MRN": "1234567",
+            "Anon_PatientID": "7654321",
+            "PatientName": "DOE^JOHN",
+            "Anon_PatientName": "ANON^JOHN",
+            "PatientBirthDate": "19700101",
+            "Search_AccessionNumber": accession_number,
+            "Anon_AccessionNumber": "ANON98765",
+            "Anon_PatientBirthDate": "19700101"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut db = BltDatabase::default();
+        let job = |name: &str| JobId::new(name.to_string());
+        db.add_study(
+            study("98765"),
+            QueryId::new("query".to_string()),
+            job("retrieve"),
+        );
+        let accession_number =
+            AccessionNumber::try_new(compact_str::CompactString::const_new("98765")).unwrap();
+        db.add_anonymization(job("anonymize"), accession_number.clone());
+        db.add_push(job("push"), accession_number.clone());
+        assert_eq!(
+            db.remove(&accession_number),
+            Some(vec![job("retrieve"), job("anonymize"), job("push")])
+        );
+        assert!(db.get(&accession_number).is_none());
+        assert!(
+            db.get_accession_number_of_anonymization(&job("anonymize"))
+                .is_none()
+        );
+        assert!(!db.has_push(&job("push")));
+        assert_eq!(db.remove(&accession_number), None);
+    }
+
+    #[test]
+    fn test_remove_study_without_jobs() {
+        let mut db = BltDatabase::default();
+        db.add_study_without_jobs(study("98765"));
+        let accession_number =
+            AccessionNumber::try_new(compact_str::CompactString::const_new("98765")).unwrap();
+        assert_eq!(db.remove(&accession_number), Some(vec![]));
+        assert!(db.get(&accession_number).is_none());
+    }
 }
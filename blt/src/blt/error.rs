@@ -34,7 +34,7 @@ impl<I: std::fmt::Debug> From<DeleteError<I>> for DoNothing {
     fn from(value: DeleteError<I>) -> Self {
         tracing::error!(
             resource = format!("{:?}", value.id),
-            code = value.code,
+            code = value.code.to_string(),
             "unsuccessful delete"
         );
         Self
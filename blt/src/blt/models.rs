@@ -1,4 +1,4 @@
-use super::dicom_date::DicomDate;
+use orthanc_sdk::api::types::DicomDate;
 
 /// Request for a study under the BLT protocol.
 
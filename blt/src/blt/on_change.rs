@@ -2,34 +2,40 @@ use super::push::push_to_peer;
 use crate::blt::BltDatabase;
 use crate::blt::error::{DoNothing, TraceAndReturn};
 use crate::blt::on_study_received::on_study_received;
-use orthanc_sdk::api::GeneralClient;
 use orthanc_sdk::api::types::{
     JobContent, JobId, JobState, MoveScuJobQueryAny, ResourceModificationContent, StudyId,
 };
+use orthanc_sdk::api::{GeneralClient, JobsClient};
 use orthanc_sdk::bindings::OrthancPluginContext;
-use orthanc_sdk::{bindings, utils::OnChangeEvent};
+use orthanc_sdk::utils::{ChangeType, ChangedResource, OnChangeEvent};
 
-pub fn on_change(
-    context: *mut OrthancPluginContext,
-    db: &mut BltDatabase,
-    OnChangeEvent {
-        change_type,
-        resource_type: _resource_type,
-        resource_id,
-    }: OnChangeEvent,
-) {
-    match change_type {
-        bindings::OrthancPluginChangeType_OrthancPluginChangeType_JobSuccess => {
-            if let Some(id) = resource_id {
-                let _ = on_job_success(context, db, JobId::new(id));
-            } else {
-                tracing::warn!("resource_id is null");
-            }
+pub fn on_change(context: *mut OrthancPluginContext, db: &mut BltDatabase, event: OnChangeEvent) {
+    match (event.change_type, event.resource()) {
+        (ChangeType::JobSuccess, Some(ChangedResource::Job(id))) => {
+            let _ = on_job_success(context, db, id);
+        }
+        (ChangeType::JobFailure, Some(ChangedResource::Job(id))) => {
+            let _ = on_job_failure(context, db, id);
         }
+        (ChangeType::JobSuccess, _) => tracing::warn!("resource_id is null"),
         _ => (),
     }
 }
 
+/// Resubmit a failed push job. Each push job is only resubmitted once.
+fn on_job_failure(
+    context: *mut OrthancPluginContext,
+    db: &mut BltDatabase,
+    id: JobId,
+) -> TraceAndReturn {
+    if !db.has_push(&id) || !db.resubmit_push(&id) {
+        return Ok(());
+    }
+    JobsClient::new(context).resubmit(&id)?;
+    tracing::warn!(job = id.to_string(), "resubmitted failed push to peer");
+    Ok(())
+}
+
 fn on_job_success(
     context: *mut OrthancPluginContext,
     db: &mut BltDatabase,
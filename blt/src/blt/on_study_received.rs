@@ -1,16 +1,14 @@
 use orthanc_sdk::api::DicomClient;
-use orthanc_sdk::api::types::{JobId, StudyId};
+use orthanc_sdk::api::types::{FindBuilder, JobId, RequestedTags, Study, StudyId, Tag, tags};
 use orthanc_sdk::bindings::OrthancPluginContext;
-use orthanc_sdk::openapi::{
-    PatientsIdAnonymizePostRequest as AnonymizePostRequest, ToolsFindPostRequest,
-};
+use orthanc_sdk::openapi::PatientsIdAnonymizePostRequest as AnonymizePostRequest;
 
 use super::BltDatabase;
 use super::error::{DoNothing, TraceAndReturn};
 use super::models::{AccessionNumber, BltStudy};
 
 /// Allow-list of DICOM tags to keep during anonymization of study.
-const TAGS_TO_KEEP: [&'static str; 2] = ["StudyDescription", "SeriesDescription"];
+const TAGS_TO_KEEP: [Tag; 2] = [tags::STUDY_DESCRIPTION, tags::SERIES_DESCRIPTION];
 
 /// Enqueue a job to anonymize the study.
 pub(crate) fn on_study_received(
@@ -37,10 +35,13 @@ pub(crate) fn on_study_received(
 fn get_series_of_retrieve_job(
     context: *mut OrthancPluginContext,
     study_instance_uid: String,
-) -> Result<Vec<StudyDetails>, DoNothing> {
-    let request = FindByStudyUID(study_instance_uid);
+) -> Result<Vec<Study<StudyRequestedTags>>, DoNothing> {
+    let request = FindBuilder::studies()
+        .query(tags::STUDY_INSTANCE_UID, study_instance_uid)
+        .case_sensitive(false)
+        .requested_tags::<StudyRequestedTags>();
     let client = DicomClient::new(context);
-    let data = client.find(request).into_result()?;
+    let data = client.find_with(&request).into_result()?;
     Ok(data)
 }
 
@@ -56,7 +57,7 @@ fn anonymize_study(
         "PatientBirthDate": blt_request.anon_patient_birth_date,
         "AccessionNumber": blt_request.anon_accession_number,
     });
-    let keep = TAGS_TO_KEEP.iter().map(|s| s.to_string()).collect();
+    let keep = TAGS_TO_KEEP.iter().map(|tag| tag.to_string()).collect();
     let request = AnonymizePostRequest {
         keep_source: Some(false),
         force: Some(true), // required to modify PatientID
@@ -69,37 +70,8 @@ fn anonymize_study(
     Ok(job.id)
 }
 
-/// A request to find a study by `StudyInstanceUID`.
-struct FindByStudyUID(pub String);
-
-impl From<FindByStudyUID> for ToolsFindPostRequest {
-    fn from(value: FindByStudyUID) -> Self {
-        ToolsFindPostRequest {
-            case_sensitive: Some(false),
-            expand: Some(true),
-            level: Some("Study".to_string()),
-            response_content: Some(vec!["RequestedTags".to_string()]),
-            query: Some(serde_json::json!({"StudyInstanceUID": value.0})),
-            requested_tags: Some(vec!["AccessionNumber".to_string()]),
-            ..Default::default()
-        }
-    }
-}
-
-impl orthanc_sdk::api::Find for FindByStudyUID {
-    type Item = StudyDetails;
-}
-
-#[derive(serde::Deserialize, Clone, Debug)]
-#[serde(rename_all = "PascalCase")]
-struct StudyDetails {
-    #[serde(rename = "ID")]
-    id: StudyId,
-    #[serde(rename = "RequestedTags")]
-    requested_tags: StudyRequestedTags,
-}
-
-#[derive(serde::Deserialize, Clone, Debug)]
+#[derive(serde::Deserialize, RequestedTags, Clone, Debug)]
+#[requested_tags(crate = "orthanc_sdk::api::types")]
 struct StudyRequestedTags {
     #[serde(rename = "AccessionNumber")]
     accession_number: AccessionNumber,
@@ -2,9 +2,10 @@
 
 use crate::blt::BltDatabase;
 use orthanc_sdk::bindings;
-use orthanc_sdk::utils::{OnChangeEvent, OnChangeThread};
-use orthanc_sdk::{create_json_rest_callback, register_on_change, register_rest};
-use std::sync::{Mutex, RwLock};
+use orthanc_sdk::utils::{ChangeType, OnChangeEvent, OnChangeThread};
+use orthanc_sdk::{catch_panic, create_json_rest_callback, register_on_change, register_rest};
+use std::sync::{Mutex, MutexGuard, RwLock};
+use tracing_subscriber::layer::SubscriberExt;
 
 static GLOBAL_STATE: RwLock<AppState> = RwLock::new(AppState {
     context: None,
@@ -31,18 +32,12 @@ struct OrthancContext(*mut bindings::OrthancPluginContext);
 unsafe impl Send for OrthancContext {}
 unsafe impl Sync for OrthancContext {}
 
-/// Orthanc configuration file.
-#[derive(serde::Deserialize)]
-#[serde(rename_all = "PascalCase")]
-struct OrthancConfig {
-    blt: Option<OrthancBltPluginConfig>,
-}
-
 /// BLT plugin configuration section of Orthanc configuration file.
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "PascalCase")]
 struct OrthancBltPluginConfig {
     verbose: Option<bool>,
+    log_level: Option<String>,
 }
 
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
@@ -50,21 +45,44 @@ struct OrthancBltPluginConfig {
 pub extern "C" fn OrthancPluginInitialize(
     context: *mut bindings::OrthancPluginContext,
 ) -> bindings::OrthancPluginErrorCode {
-    let logger = orthanc_sdk::OrthancLogger {
-        context,
-        plugin_name: plugin_name!(),
-        verbose: get_config(context).and_then(|c| c.verbose).unwrap_or(false),
+    catch_panic(|| initialize(context))
+}
+
+fn initialize(context: *mut bindings::OrthancPluginContext) -> bindings::OrthancPluginErrorCode {
+    let config = orthanc_sdk::get_plugin_config::<OrthancBltPluginConfig>(context, "Blt");
+    let mut logger = orthanc_sdk::OrthancLogger::new(context, plugin_name!());
+    logger.verbose = config
+        .as_ref()
+        .ok()
+        .and_then(|c| c.verbose)
+        .unwrap_or(false);
+    let directives = config
+        .as_ref()
+        .ok()
+        .and_then(|c| c.log_level.clone())
+        .unwrap_or_default();
+    let (filter, filter_error) = match orthanc_sdk::log_filter(&directives) {
+        Ok(filter) => (filter, None),
+        Err(e) => (orthanc_sdk::log_filter("").unwrap(), Some(e)),
     };
-    if let Err(e) = tracing::subscriber::set_global_default(logger)
+    let subscriber = tracing_subscriber::registry().with(filter).with(logger);
+    if let Err(e) = tracing::subscriber::set_global_default(subscriber)
         && !e.to_string().contains("has already been set")
     {
         eprintln!("Failed to initialize logging in Rust plugin: {e}");
     }
+    if let Some(e) = filter_error {
+        tracing::warn!(LogLevel = directives, "invalid log level directives: {e}");
+    }
+    if let Err(e) = config {
+        tracing::error!("invalid configuration: {e}");
+        return bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_BadFileFormat;
+    }
 
     let mut app_state = GLOBAL_STATE.try_write().unwrap();
     app_state.context = Some(OrthancContext(context));
 
-    let mut db_mutex = DATABASE.lock().unwrap();
+    let mut db_mutex = lock_database();
     let _ = db_mutex.insert(BltDatabase::with_capacity(1000));
 
     app_state.on_change_thread = Some(OnChangeThread::spawn(move |event| {
@@ -74,33 +92,41 @@ pub extern "C" fn OrthancPluginInitialize(
         //       synchronous calls to the Orthanc built-in API. This is yet another
         //       issue which will magically go away by replacing the in-process db
         //       with ValKey.
-        let mut db_mutex = DATABASE.lock().unwrap();
+        let mut db_mutex = lock_database();
         let database = db_mutex.as_mut().unwrap();
         crate::blt::on_change(context, database, event);
     }));
 
+    crate::blt::register_error_codes(context);
     register_on_change(context, Some(on_change));
     register_rest(context, "/blt/studies", Some(rest_callback));
+    register_rest(context, "/blt/studies/([^/]+)", Some(rest_callback));
 
     bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_Success
 }
 
-fn get_config(context: *mut bindings::OrthancPluginContext) -> Option<OrthancBltPluginConfig> {
-    let buffer = orthanc_sdk::get_configuration(context)?;
-    let config: OrthancConfig = buffer.deserialize().ok()?;
-    config.blt
+/// Lock [DATABASE], recovering it if a previous holder of the lock panicked.
+fn lock_database() -> MutexGuard<'static, Option<BltDatabase>> {
+    DATABASE.lock().unwrap_or_else(|poisoned| {
+        tracing::warn!("database mutex was poisoned by a panic, recovering");
+        DATABASE.clear_poison();
+        poisoned.into_inner()
+    })
 }
 
 #[unsafe(no_mangle)]
 pub extern "C" fn OrthancPluginFinalize() {
-    let mut app_state = GLOBAL_STATE.try_write().expect("unable to obtain lock");
-    if let Some(thread) = app_state.on_change_thread.take() {
-        thread.join().unwrap()
-    }
-    let mut db_mutex = DATABASE.lock().unwrap();
-    if let Some(hashmap) = db_mutex.take() {
-        drop(hashmap);
-    }
+    catch_panic(|| {
+        let mut app_state = GLOBAL_STATE.try_write().expect("unable to obtain lock");
+        if let Some(thread) = app_state.on_change_thread.take() {
+            thread.join().unwrap()
+        }
+        let mut db_mutex = lock_database();
+        if let Some(hashmap) = db_mutex.take() {
+            drop(hashmap);
+        }
+        bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_Success
+    });
 }
 
 #[unsafe(no_mangle)]
@@ -118,22 +144,28 @@ extern "C" fn on_change(
     resource_type: bindings::OrthancPluginResourceType,
     resource_id: *const std::ffi::c_char,
 ) -> bindings::OrthancPluginErrorCode {
-    let resource_id = if resource_id.is_null() {
-        None
-    } else if let Ok(cstr) = unsafe { std::ffi::CStr::from_ptr(resource_id).to_str() } {
-        Some(cstr.to_string())
-    } else {
-        tracing::warn!("resource_id is not UTF-8");
-        return bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_InternalError;
+    catch_panic(|| send_on_change_event(change_type, resource_type, resource_id))
+}
+
+fn send_on_change_event(
+    change_type: bindings::OrthancPluginChangeType,
+    resource_type: bindings::OrthancPluginResourceType,
+    resource_id: *const std::ffi::c_char,
+) -> bindings::OrthancPluginErrorCode {
+    let event = match unsafe { OnChangeEvent::try_new(change_type, resource_type, resource_id) } {
+        Ok(event) => event,
+        Err(e) => {
+            tracing::warn!("{e}");
+            return bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_InternalError;
+        }
     };
+    // ignore change types which are unknown to orthanc_sdk, e.g. from a newer Orthanc
+    if let ChangeType::Other(_) = event.change_type {
+        return bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_Success;
+    }
     if let Ok(app) = GLOBAL_STATE.try_read()
         && let Some(channel) = &app.on_change_thread
     {
-        let event = OnChangeEvent {
-            change_type,
-            resource_type,
-            resource_id,
-        };
         if channel.send(event).is_ok() {
             bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_Success
         } else {
@@ -149,21 +181,18 @@ extern "C" fn rest_callback(
     url: *const std::os::raw::c_char,
     request: *const bindings::OrthancPluginHttpRequest,
 ) -> bindings::OrthancPluginErrorCode {
-    let app_state = if let Ok(app_state) = GLOBAL_STATE.try_read() {
-        app_state
-    } else {
-        tracing::error!("Failed to read GLOBAL_STATE");
-        return bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_InternalError;
-    };
-    let mut db_mutex = if let Ok(db_mutex) = DATABASE.lock() {
-        db_mutex
-    } else {
-        tracing::error!("Failed to lock database mutex, did a background thread panic?");
-        return bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_InternalError;
-    };
-    let context = app_state.context.as_ref().unwrap().0;
-    let database = db_mutex.as_mut().unwrap();
-    create_json_rest_callback(context, output, url, request, |req| {
-        crate::blt::route_http_request(context, req, database)
+    catch_panic(|| {
+        let app_state = if let Ok(app_state) = GLOBAL_STATE.try_read() {
+            app_state
+        } else {
+            tracing::error!("Failed to read GLOBAL_STATE");
+            return bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_InternalError;
+        };
+        let mut db_mutex = lock_database();
+        let context = app_state.context.as_ref().unwrap().0;
+        let database = db_mutex.as_mut().unwrap();
+        create_json_rest_callback(context, output, url, request, |req| {
+            crate::blt::route_http_request(context, req, database)
+        })
     })
 }
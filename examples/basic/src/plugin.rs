@@ -18,6 +18,7 @@
 
 use std::io::Write;
 use std::sync::RwLock;
+use tracing_subscriber::layer::SubscriberExt;
 
 use orthanc_sdk::api::types::{Patient, PatientId};
 use orthanc_sdk::bindings;
@@ -73,14 +74,8 @@ pub extern "C" fn OrthancPluginGetVersion() -> *const u8 {
     concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr()
 }
 
-/// Entire Orthanc configuration JSON file content.
-#[derive(serde::Deserialize)]
-struct OrthancConfiguration {
-    #[serde(rename = "ExampleRustPlugin")]
-    example_rust_plugin: Option<ExamplePluginConfig>,
-}
-
-/// Configuration of just this plugin.
+/// Configuration of just this plugin, i.e. the `"ExampleRustPlugin"` section
+/// of the Orthanc configuration file.
 #[derive(serde::Deserialize, Default)]
 struct ExamplePluginConfig {
     #[serde(rename = "MrnFile")]
@@ -98,15 +93,19 @@ struct ExamplePluginConfig {
 pub extern "C" fn OrthancPluginInitialize(
     context: *mut bindings::OrthancPluginContext,
 ) -> bindings::OrthancPluginErrorCode {
+    // A panic must never unwind into Orthanc's C++ code, see [on_change_callback].
+    orthanc_sdk::catch_panic(|| initialize(context))
+}
+
+fn initialize(context: *mut bindings::OrthancPluginContext) -> bindings::OrthancPluginErrorCode {
     // Create a logger which reports log messages using Orthanc Toolbox's built-in
     // logging system.
-    let logger = orthanc_sdk::OrthancLogger {
-        context,
-        plugin_name: plugin_name!(),
-        verbose: true,
-    };
+    let mut logger = orthanc_sdk::OrthancLogger::new(context, plugin_name!());
+    logger.verbose = true;
     // Set the global `tracing` subscriber. `tracing` is the de-facto crate for Rust logging.
-    if let Err(e) = tracing::subscriber::set_global_default(logger)
+    // The logger is a layer, so it can be composed with other layers such as filters.
+    let subscriber = tracing_subscriber::registry().with(logger);
+    if let Err(e) = tracing::subscriber::set_global_default(subscriber)
     // caveat: OrthancPluginInitialize might be called twice or more (if `/tools/reset`
     // is called on the REST API), so we should ignore the error when this happens.
         && !e.to_string().contains("has already been set")
@@ -118,13 +117,19 @@ pub extern "C" fn OrthancPluginInitialize(
     // Store the [bindings::OrthancPluginContext] instance in the global variable
     app_state.context = Some(OrthancContext(context));
 
-    // Read the Orthanc configuration
-    let configuration: OrthancConfiguration = orthanc_sdk::get_configuration(context)
-        .unwrap()
-        .deserialize()
-        .unwrap();
+    // Read the plugin configuration section. Errors have the path of the invalid value,
+    // and environment variables such as `ORTHANC__EXAMPLE_RUST_PLUGIN__MRN_FILE`
+    // override values from the configuration file.
+    let config = match orthanc_sdk::get_plugin_config(context, "ExampleRustPlugin") {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::error!("invalid configuration: {e}");
+            // Returning an error code makes Orthanc stop with an error.
+            return bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_BadFileFormat;
+        }
+    };
     // Store the plugin configuration section in the global state
-    app_state.config = Some(configuration.example_rust_plugin.unwrap_or_default());
+    app_state.config = Some(config);
 
     // Spawn a background thread to handle OnChange events.
     app_state.on_change_thread = Some(orthanc_sdk::utils::OnChangeThread::spawn(move |event| {
@@ -145,12 +150,15 @@ pub extern "C" fn OrthancPluginInitialize(
 /// Called when Orthanc is shutting down. The plugin must release all allocated resources.
 #[unsafe(no_mangle)]
 pub extern "C" fn OrthancPluginFinalize() {
-    let mut app_state = GLOBAL_STATE.try_write().expect("unable to obtain lock");
-    if let Some(thread) = app_state.on_change_thread.take() {
-        thread.join().unwrap()
-    }
-    app_state.context = None;
-    app_state.config = None;
+    orthanc_sdk::catch_panic(|| {
+        let mut app_state = GLOBAL_STATE.try_write().expect("unable to obtain lock");
+        if let Some(thread) = app_state.on_change_thread.take() {
+            thread.join().unwrap()
+        }
+        app_state.context = None;
+        app_state.config = None;
+        bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_Success
+    });
 }
 
 /// The OnChange callback function invoked directly by Orthanc.
@@ -161,24 +169,35 @@ extern "C" fn on_change_callback(
     resource_type: bindings::OrthancPluginResourceType,
     resource_id: *const std::ffi::c_char,
 ) -> bindings::OrthancPluginErrorCode {
-    // read resource_id as a Rust-friendly type
-    let resource_id = if resource_id.is_null() {
-        None
-    } else if let Ok(cstr) = unsafe { std::ffi::CStr::from_ptr(resource_id).to_str() } {
-        Some(cstr.to_string())
-    } else {
-        tracing::warn!("resource_id is not UTF-8");
-        return bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_InternalError;
+    // A panic must never unwind into Orthanc's C++ code. `catch_panic` logs
+    // the panic and returns an error code instead.
+    orthanc_sdk::catch_panic(|| send_on_change_event(change_type, resource_type, resource_id))
+}
+
+/// Send an OnChange event to the background thread's channel.
+fn send_on_change_event(
+    change_type: bindings::OrthancPluginChangeType,
+    resource_type: bindings::OrthancPluginResourceType,
+    resource_id: *const std::ffi::c_char,
+) -> bindings::OrthancPluginErrorCode {
+    // read the parameters as Rust-friendly types
+    let event = match unsafe {
+        orthanc_sdk::utils::OnChangeEvent::try_new(change_type, resource_type, resource_id)
+    } {
+        Ok(event) => event,
+        Err(e) => {
+            tracing::warn!("{e}");
+            return bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_InternalError;
+        }
     };
+    // ignore change types which are unknown to orthanc_sdk, e.g. from a newer Orthanc
+    if let orthanc_sdk::utils::ChangeType::Other(_) = event.change_type {
+        return bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_Success;
+    }
     // Get the global app state
     if let Ok(app) = GLOBAL_STATE.try_read()
         && let Some(channel) = &app.on_change_thread
     {
-        let event = orthanc_sdk::utils::OnChangeEvent {
-            change_type,
-            resource_type,
-            resource_id,
-        };
         if channel.send(event).is_ok() {
             bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_Success
         } else {
@@ -199,18 +218,14 @@ extern "C" fn on_change_callback(
 fn on_change_handler(
     context: *mut bindings::OrthancPluginContext,
     config: &ExamplePluginConfig,
-    orthanc_sdk::utils::OnChangeEvent {
-        change_type,
-        resource_type,
-        resource_id,
-    }: orthanc_sdk::utils::OnChangeEvent,
+    event: orthanc_sdk::utils::OnChangeEvent,
 ) {
-    if change_type == bindings::OrthancPluginChangeType_OrthancPluginChangeType_NewPatient
-        && resource_type == bindings::OrthancPluginResourceType_OrthancPluginResourceType_Patient
-        && let Some(patient_id) = resource_id
+    use orthanc_sdk::utils::{ChangeType, ChangedResource};
+    // `event.resource()` produces a typed ID according to the type of change and resource.
+    if event.change_type == ChangeType::NewPatient
+        && let Some(ChangedResource::Patient(patient_id)) = event.resource()
         && let Some(output_file) = &config.output_file
     {
-        let patient_id = PatientId::new(patient_id);
         let _ = mrn_append_to_file(context, patient_id, output_file);
     }
 }
@@ -234,18 +249,13 @@ fn mrn_append_to_file(
 }
 
 /// The patient-level DICOM tags we care about getting back from the Orthanc API.
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Deserialize, orthanc_sdk::api::types::RequestedTags, Debug)]
+#[requested_tags(crate = "orthanc_sdk::api::types")]
 struct PatientDetails {
     #[serde(rename = "PatientID")]
     mrn: String,
 }
 
-impl orthanc_sdk::api::types::RequestedTags for PatientDetails {
-    fn names() -> &'static [&'static str] {
-        &["PatientID"] // field names of `PatientDetails`
-    }
-}
-
 /// The HTTP handler callback function invoked directly by Orthanc.
 ///
 /// It does plumbing before calling [http_route_add].
@@ -254,16 +264,20 @@ extern "C" fn rest_callback(
     url: *const std::ffi::c_char,
     request: *const bindings::OrthancPluginHttpRequest,
 ) -> bindings::OrthancPluginErrorCode {
-    let app_state = if let Ok(app_state) = GLOBAL_STATE.try_read() {
-        app_state
-    } else {
-        tracing::error!("Failed to read GLOBAL_STATE");
-        return bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_InternalError;
-    };
-    let context = app_state.context.as_ref().unwrap().0;
-    // `create_json_rest_callback` is a helper function which adapts FFI
-    // to Rust-friendly data types.
-    orthanc_sdk::create_json_rest_callback(context, output, url, request, |req| http_route_add(req))
+    orthanc_sdk::catch_panic(|| {
+        let app_state = if let Ok(app_state) = GLOBAL_STATE.try_read() {
+            app_state
+        } else {
+            tracing::error!("Failed to read GLOBAL_STATE");
+            return bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_InternalError;
+        };
+        let context = app_state.context.as_ref().unwrap().0;
+        // `create_json_rest_callback` is a helper function which adapts FFI
+        // to Rust-friendly data types. It also catches any panic in `http_route_add`.
+        orthanc_sdk::create_json_rest_callback(context, output, url, request, |req| {
+            http_route_add(req)
+        })
+    })
 }
 
 /// HTTP route which adds two integers.
@@ -1,26 +1,46 @@
+use crate::Timestamp;
 use crate::types::{InstanceId, PatientId, SeriesId, StudyId};
+use compact_str::CompactString;
 use serde::{Deserialize, Serialize};
 
 /// Orthanc patient detail response from
-/// [`/patients/{id}`](https://orthanc.uclouvain.be/api/#tag/Patients/paths/~1patients~1{id}/get)
-/// (incomplete)
+/// [`/patients/{id}`](https://orthanc.uclouvain.be/api/#tag/Patients/paths/~1patients~1{id}/get).
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "PascalCase")]
 pub struct Patient<T> {
     #[serde(rename = "ID")]
     pub id: PatientId,
 
+    /// Whether the patient is protected against recycling.
+    #[serde(default)]
+    pub is_protected: bool,
+    pub is_stable: bool,
+    /// Labels of the resource. Empty for Orthanc before 1.12.0, which has no labels.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    pub last_update: Timestamp,
+    pub main_dicom_tags: PatientMainDicomTags,
+    pub studies: Vec<StudyId>,
     pub requested_tags: T,
 }
 
 /// Orthanc study detail response from
-/// [`/studies/{id}`](https://orthanc.uclouvain.be/api/#tag/Studies/paths/~1studies~1{id}/get)
-/// (incomplete)
+/// [`/studies/{id}`](https://orthanc.uclouvain.be/api/#tag/Studies/paths/~1studies~1{id}/get).
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "PascalCase")]
 pub struct Study<T> {
     #[serde(rename = "ID")]
     pub id: StudyId,
+
+    pub is_stable: bool,
+    /// Labels of the resource. Empty for Orthanc before 1.12.0, which has no labels.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    pub last_update: Timestamp,
+    pub main_dicom_tags: StudyMainDicomTags,
+    pub parent_patient: PatientId,
+    pub patient_main_dicom_tags: PatientMainDicomTags,
+    pub series: Vec<SeriesId>,
     pub requested_tags: T,
 }
 
@@ -37,18 +57,13 @@ pub struct Series<T> {
     pub instances: Vec<InstanceId>,
 
     pub is_stable: bool,
+    /// Labels of the resource. Empty for Orthanc before 1.12.0, which has no labels.
+    #[serde(default)]
     pub labels: Vec<String>,
-    pub last_update: String,
+    pub last_update: Timestamp,
     pub parent_study: StudyId,
     pub status: SeriesStatus,
-
-    /// Main DICOM tags.
-    ///
-    /// Ref: <https://orthanc.uclouvain.be/book/faq/main-dicom-tags.html>
-    ///
-    /// Note: the schema of "MainDicomTags" is customizable by the
-    /// Orthanc configuration, so it cannot have a static type.
-    pub main_dicom_tags: serde_json::Value,
+    pub main_dicom_tags: SeriesMainDicomTags,
     pub requested_tags: T,
 }
 
@@ -64,16 +79,163 @@ pub enum SeriesStatus {
 }
 
 /// Orthanc instance details response from
-/// [`/instances/{id}`](https://orthanc.uclouvain.be/api/#tag/Instances/paths/~1instances~1{id}/get)
-/// (incomplete)
+/// [`/instances/{id}`](https://orthanc.uclouvain.be/api/#tag/Instances/paths/~1instances~1{id}/get).
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "PascalCase")]
 pub struct Instance<T> {
     #[serde(rename = "ID")]
     pub id: InstanceId,
+
+    /// Size of the DICOM file in bytes.
+    pub file_size: u64,
+    /// UUID of the DICOM file in the storage area of Orthanc.
+    pub file_uuid: String,
+    /// Position of the instance in its series, if known.
+    pub index_in_series: Option<u32>,
+    /// Labels of the resource. Empty for Orthanc before 1.12.0, which has no labels.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    pub main_dicom_tags: InstanceMainDicomTags,
+    pub parent_series: SeriesId,
     pub requested_tags: T,
 }
 
+// NOTE: the schema of "MainDicomTags" is customizable by the Orthanc
+//       configuration, so the structs below only have fields for the
+//       default main DICOM tags. Any other tags are kept in `other`.
+
+/// Main DICOM tags of a patient.
+///
+/// Ref: <https://orthanc.uclouvain.be/book/faq/main-dicom-tags.html>
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PatientMainDicomTags {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patient_name: Option<CompactString>,
+    #[serde(rename = "PatientID", default, skip_serializing_if = "Option::is_none")]
+    pub patient_id: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patient_birth_date: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patient_sex: Option<CompactString>,
+    #[serde(
+        rename = "OtherPatientIDs",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub other_patient_ids: Option<CompactString>,
+
+    /// Main DICOM tags which do not have a field in this struct.
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Main DICOM tags of a study.
+///
+/// Ref: <https://orthanc.uclouvain.be/book/faq/main-dicom-tags.html>
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct StudyMainDicomTags {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accession_number: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub institution_name: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub referring_physician_name: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requested_procedure_description: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requesting_physician: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub study_date: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub study_description: Option<CompactString>,
+    #[serde(rename = "StudyID", default, skip_serializing_if = "Option::is_none")]
+    pub study_id: Option<CompactString>,
+    #[serde(
+        rename = "StudyInstanceUID",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub study_instance_uid: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub study_time: Option<CompactString>,
+
+    /// Main DICOM tags which do not have a field in this struct.
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Main DICOM tags of a series.
+///
+/// Ref: <https://orthanc.uclouvain.be/book/faq/main-dicom-tags.html>
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct SeriesMainDicomTags {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_part_examined: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manufacturer: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modality: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operators_name: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_name: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence_name: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series_date: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series_description: Option<CompactString>,
+    #[serde(
+        rename = "SeriesInstanceUID",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub series_instance_uid: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series_number: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series_time: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub station_name: Option<CompactString>,
+
+    /// Main DICOM tags which do not have a field in this struct.
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Main DICOM tags of an instance.
+///
+/// Ref: <https://orthanc.uclouvain.be/book/faq/main-dicom-tags.html>
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct InstanceMainDicomTags {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acquisition_number: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_comments: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_creation_date: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_creation_time: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_number: Option<CompactString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number_of_frames: Option<CompactString>,
+    #[serde(
+        rename = "SOPInstanceUID",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sop_instance_uid: Option<CompactString>,
+
+    /// Main DICOM tags which do not have a field in this struct.
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
 /// Marker trait for DICOM resources stored in Orthanc.
 pub trait DicomResource<T> {}
 impl<T> DicomResource<T> for Patient<T> {}
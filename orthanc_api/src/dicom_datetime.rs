@@ -0,0 +1,515 @@
+//! DICOM date and time value representations.
+//!
+//! Ref: <https://dicom.nema.org/medical/dicom/current/output/chtml/part05/sect_6.2.html>
+
+use crate::timestamp::{days_in_month, digits};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::fmt;
+use std::ops::{RangeFrom, RangeInclusive, RangeToInclusive};
+use std::str::FromStr;
+
+/// A DICOM date (VR `DA`), e.g. `"20250703"`.
+///
+/// For compatibility with data entered by hand, dates in the US format
+/// `M/D/YYYY` are also accepted by [FromStr] and when deserializing.
+/// Dates are always written as `YYYYMMDD`.
+#[derive(
+    Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, SerializeDisplay, DeserializeFromStr,
+)]
+pub struct DicomDate {
+    // NOTE: the order of fields is significant for the derived Ord
+    year: u16,
+    month: u8,
+    day: u8,
+}
+
+impl DicomDate {
+    /// Create a [DicomDate], returning [None] if it is not a valid date.
+    pub fn new(year: u16, month: u8, day: u8) -> Option<Self> {
+        let valid = year <= 9999
+            && (1..=12).contains(&month)
+            && (1..=days_in_month(year, month)).contains(&day);
+        valid.then_some(Self { year, month, day })
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+}
+
+impl FromStr for DicomDate {
+    type Err = InvalidDicomDateTime;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_date(s).ok_or_else(|| InvalidDicomDateTime(s.to_string()))
+    }
+}
+
+/// Parse `YYYYMMDD` or `M/D/YYYY`.
+fn parse_date(s: &str) -> Option<DicomDate> {
+    if s.contains('/') {
+        let mut iter = s.split('/');
+        let month = iter.next().filter(|m| (1..=2).contains(&m.len()))?;
+        let day = iter.next().filter(|d| (1..=2).contains(&d.len()))?;
+        let year = iter.next().filter(|y| y.len() == 4)?;
+        if iter.next().is_some() {
+            return None;
+        }
+        DicomDate::new(
+            digits(year)? as u16,
+            digits(month)? as u8,
+            digits(day)? as u8,
+        )
+    } else {
+        parse_dicom_date(s)
+    }
+}
+
+/// Parse `YYYYMMDD`, the only date format of DICOM.
+fn parse_dicom_date(s: &str) -> Option<DicomDate> {
+    if s.len() != 8 || !s.is_ascii() {
+        return None;
+    }
+    DicomDate::new(
+        digits(&s[0..4])? as u16,
+        digits(&s[4..6])? as u8,
+        digits(&s[6..8])? as u8,
+    )
+}
+
+impl fmt::Display for DicomDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}{:02}{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// A DICOM time (VR `TM`), e.g. `"002648.691947"`.
+///
+/// Components after the hour are optional in DICOM, e.g. `"00"` and `"0026"`
+/// are valid times. The precision of the value is preserved when it is written,
+/// except that the fraction of a second is always written with 6 digits.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, SerializeDisplay, DeserializeFromStr)]
+pub struct DicomTime {
+    hour: u8,
+    minute: Option<u8>,
+    second: Option<u8>,
+    microsecond: Option<u32>,
+}
+
+impl DicomTime {
+    /// Create a [DicomTime] with a precision of seconds, or microseconds if
+    /// `microsecond` is not zero. Returns [None] if it is not a valid time.
+    pub fn new(hour: u8, minute: u8, second: u8, microsecond: u32) -> Option<Self> {
+        let microsecond = (microsecond != 0).then_some(microsecond);
+        Self::with_precision(hour, Some(minute), Some(second), microsecond)
+    }
+
+    fn with_precision(
+        hour: u8,
+        minute: Option<u8>,
+        second: Option<u8>,
+        microsecond: Option<u32>,
+    ) -> Option<Self> {
+        let valid = hour < 24
+            && minute.is_none_or(|m| m < 60)
+            && second.is_none_or(|s| s < 60 && minute.is_some())
+            && microsecond.is_none_or(|us| us < 1_000_000 && second.is_some());
+        valid.then_some(Self {
+            hour,
+            minute,
+            second,
+            microsecond,
+        })
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    /// Minute, or [None] if the time only specifies the hour.
+    pub fn minute(&self) -> Option<u8> {
+        self.minute
+    }
+
+    /// Second, or [None] if the time does not specify seconds.
+    pub fn second(&self) -> Option<u8> {
+        self.second
+    }
+
+    /// Fractional part of the second, in microseconds.
+    pub fn microsecond(&self) -> Option<u32> {
+        self.microsecond
+    }
+}
+
+impl FromStr for DicomTime {
+    type Err = InvalidDicomDateTime;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_time(s).ok_or_else(|| InvalidDicomDateTime(s.to_string()))
+    }
+}
+
+/// Parse `HH[MM[SS[.F{1,6}]]]`.
+fn parse_time(s: &str) -> Option<DicomTime> {
+    let (hms, fraction) = match s.split_once('.') {
+        Some((hms, fraction)) => (hms, Some(fraction)),
+        None => (s, None),
+    };
+    let component = |i: usize| -> Option<Option<u8>> {
+        match hms.get(i..i + 2) {
+            Some(c) => digits(c).map(|n| Some(n as u8)),
+            None => Some(None),
+        }
+    };
+    if !hms.is_ascii() || !matches!(hms.len(), 2 | 4 | 6) || (fraction.is_some() && hms.len() != 6)
+    {
+        return None;
+    }
+    let microsecond = match fraction {
+        Some(f) if (1..=6).contains(&f.len()) => Some(digits(f)? * 10u32.pow(6 - f.len() as u32)),
+        Some(_) => return None,
+        None => None,
+    };
+    DicomTime::with_precision(component(0)??, component(2)?, component(4)?, microsecond)
+}
+
+impl fmt::Display for DicomTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}", self.hour)?;
+        if let Some(minute) = self.minute {
+            write!(f, "{minute:02}")?;
+        }
+        if let Some(second) = self.second {
+            write!(f, "{second:02}")?;
+        }
+        if let Some(microsecond) = self.microsecond {
+            write!(f, ".{microsecond:06}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A DICOM date-time (VR `DT`), e.g. `"20250703002648.691947-0400"`.
+///
+/// Only date-times with a complete date are supported, i.e. the partial
+/// values `YYYY` and `YYYYMM` are not.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, SerializeDisplay, DeserializeFromStr)]
+pub struct DicomDateTime {
+    date: DicomDate,
+    time: Option<DicomTime>,
+    offset_minutes: Option<i16>,
+}
+
+impl DicomDateTime {
+    /// Create a [DicomDateTime] without a UTC offset.
+    pub fn new(date: DicomDate, time: Option<DicomTime>) -> Self {
+        Self {
+            date,
+            time,
+            offset_minutes: None,
+        }
+    }
+
+    /// Set the offset from UTC in minutes, returning [None] if it is not
+    /// within ±14 hours.
+    pub fn with_offset_minutes(self, offset_minutes: i16) -> Option<Self> {
+        (offset_minutes.abs() <= 14 * 60).then_some(Self {
+            offset_minutes: Some(offset_minutes),
+            ..self
+        })
+    }
+
+    pub fn date(&self) -> DicomDate {
+        self.date
+    }
+
+    pub fn time(&self) -> Option<DicomTime> {
+        self.time
+    }
+
+    /// Offset from UTC in minutes, if specified.
+    pub fn offset_minutes(&self) -> Option<i16> {
+        self.offset_minutes
+    }
+}
+
+impl FromStr for DicomDateTime {
+    type Err = InvalidDicomDateTime;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_date_time(s).ok_or_else(|| InvalidDicomDateTime(s.to_string()))
+    }
+}
+
+/// Parse `YYYYMMDD[HH[MM[SS[.F{1,6}]]]][&ZZXX]`.
+fn parse_date_time(s: &str) -> Option<DicomDateTime> {
+    let (s, offset) = match s.find(['+', '-']) {
+        Some(i) => (&s[..i], Some(&s[i..])),
+        None => (s, None),
+    };
+    if s.len() < 8 || !s.is_ascii() {
+        return None;
+    }
+    let (date, time) = s.split_at(8);
+    let date = parse_dicom_date(date)?;
+    let time = if time.is_empty() {
+        None
+    } else {
+        Some(parse_time(time)?)
+    };
+    let value = DicomDateTime::new(date, time);
+    match offset {
+        Some(offset) if offset.len() == 5 && offset.is_ascii() => {
+            let hours = digits(&offset[1..3])? as i16;
+            let minutes = digits(&offset[3..5])? as i16;
+            if minutes >= 60 {
+                return None;
+            }
+            let sign = if offset.starts_with('-') { -1 } else { 1 };
+            value.with_offset_minutes(sign * (hours * 60 + minutes))
+        }
+        Some(_) => None,
+        None => Some(value),
+    }
+}
+
+impl fmt::Display for DicomDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.date)?;
+        if let Some(time) = self.time {
+            write!(f, "{time}")?;
+        }
+        if let Some(offset) = self.offset_minutes {
+            let sign = if offset < 0 { '-' } else { '+' };
+            let offset = offset.unsigned_abs();
+            write!(f, "{sign}{:02}{:02}", offset / 60, offset % 60)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error parsing a [DicomDate], [DicomTime], [DicomDateTime] or [DicomRange].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidDicomDateTime(pub String);
+
+impl fmt::Display for InvalidDicomDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid DICOM date or time: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidDicomDateTime {}
+
+/// A range of DICOM dates or times for
+/// [range matching](https://dicom.nema.org/medical/dicom/current/output/chtml/part04/sect_C.2.2.2.5.html)
+/// in queries, e.g. `"20200101-20201231"`.
+///
+/// ## Example
+///
+/// ```
+/// use orthanc_api::{DicomDate, DicomRange};
+///
+/// let start = DicomDate::new(2020, 1, 1).unwrap();
+/// let end = DicomDate::new(2020, 12, 31).unwrap();
+/// let query = serde_json::json!({
+///     "Level": "Study",
+///     "Query": { "StudyDate": DicomRange::Between(start, end) }
+/// });
+/// assert_eq!(query["Query"]["StudyDate"], "20200101-20201231");
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum DicomRange<T> {
+    /// Match a single value.
+    Exact(T),
+    /// Match values between the two values, inclusive.
+    Between(T, T),
+    /// Match values on or after the value.
+    Since(T),
+    /// Match values on or before the value.
+    Until(T),
+}
+
+impl<T: Ord> DicomRange<T> {
+    /// Returns `true` if `value` is within the range.
+    pub fn contains(&self, value: &T) -> bool {
+        match self {
+            DicomRange::Exact(v) => value == v,
+            DicomRange::Between(start, end) => start <= value && value <= end,
+            DicomRange::Since(start) => start <= value,
+            DicomRange::Until(end) => value <= end,
+        }
+    }
+}
+
+impl<T> From<T> for DicomRange<T> {
+    fn from(value: T) -> Self {
+        Self::Exact(value)
+    }
+}
+
+impl<T> From<RangeInclusive<T>> for DicomRange<T> {
+    fn from(value: RangeInclusive<T>) -> Self {
+        let (start, end) = value.into_inner();
+        Self::Between(start, end)
+    }
+}
+
+impl<T> From<RangeFrom<T>> for DicomRange<T> {
+    fn from(value: RangeFrom<T>) -> Self {
+        Self::Since(value.start)
+    }
+}
+
+impl<T> From<RangeToInclusive<T>> for DicomRange<T> {
+    fn from(value: RangeToInclusive<T>) -> Self {
+        Self::Until(value.end)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for DicomRange<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DicomRange::Exact(value) => write!(f, "{value}"),
+            DicomRange::Between(start, end) => write!(f, "{start}-{end}"),
+            DicomRange::Since(start) => write!(f, "{start}-"),
+            DicomRange::Until(end) => write!(f, "-{end}"),
+        }
+    }
+}
+
+impl<T: FromStr> FromStr for DicomRange<T> {
+    type Err = InvalidDicomDateTime;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(value) = s.parse() {
+            return Ok(Self::Exact(value));
+        }
+        // A DT value may contain '-' in its UTC offset, so try every '-'
+        // as the separator of the range.
+        s.match_indices('-')
+            .find_map(|(i, _)| {
+                let (start, end) = (&s[..i], &s[i + 1..]);
+                match (start.is_empty(), end.is_empty()) {
+                    (true, true) => None,
+                    (true, false) => end.parse().ok().map(Self::Until),
+                    (false, true) => start.parse().ok().map(Self::Since),
+                    (false, false) => Some(Self::Between(start.parse().ok()?, end.parse().ok()?)),
+                }
+            })
+            .ok_or_else(|| InvalidDicomDateTime(s.to_string()))
+    }
+}
+
+impl<T: fmt::Display> Serialize for DicomRange<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de, T: FromStr> Deserialize<'de> for DicomRange<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "jiff")]
+mod jiff_conversions {
+    use super::*;
+    use jiff::civil;
+
+    impl DicomDate {
+        /// Get the date as [jiff::civil::Date].
+        pub fn to_date(&self) -> civil::Date {
+            civil::date(self.year as i16, self.month as i8, self.day as i8)
+        }
+    }
+
+    impl From<DicomDate> for civil::Date {
+        fn from(value: DicomDate) -> Self {
+            value.to_date()
+        }
+    }
+
+    impl TryFrom<civil::Date> for DicomDate {
+        type Error = InvalidDicomDateTime;
+
+        fn try_from(value: civil::Date) -> Result<Self, Self::Error> {
+            u16::try_from(value.year())
+                .ok()
+                .and_then(|year| DicomDate::new(year, value.month() as u8, value.day() as u8))
+                .ok_or_else(|| InvalidDicomDateTime(value.to_string()))
+        }
+    }
+
+    impl DicomTime {
+        /// Get the time as [jiff::civil::Time]. Unspecified components are zero.
+        pub fn to_time(&self) -> civil::Time {
+            civil::time(
+                self.hour as i8,
+                self.minute.unwrap_or(0) as i8,
+                self.second.unwrap_or(0) as i8,
+                self.microsecond.unwrap_or(0) as i32 * 1000,
+            )
+        }
+    }
+
+    impl From<DicomTime> for civil::Time {
+        fn from(value: DicomTime) -> Self {
+            value.to_time()
+        }
+    }
+
+    impl From<civil::Time> for DicomTime {
+        /// Convert a [jiff::civil::Time], truncating it to microseconds.
+        fn from(value: civil::Time) -> Self {
+            DicomTime::new(
+                value.hour() as u8,
+                value.minute() as u8,
+                value.second() as u8,
+                value.subsec_nanosecond() as u32 / 1000,
+            )
+            .expect("jiff::civil::Time is always a valid time")
+        }
+    }
+
+    impl DicomDateTime {
+        /// Get the date-time as [jiff::civil::DateTime], ignoring its UTC offset.
+        pub fn to_datetime(&self) -> civil::DateTime {
+            let time = self.time.map(|t| t.to_time()).unwrap_or_default();
+            self.date.to_date().to_datetime(time)
+        }
+
+        /// Get the instant in time, or [None] if the date-time does not have
+        /// a UTC offset.
+        pub fn to_jiff_timestamp(&self) -> Option<jiff::Timestamp> {
+            let offset = jiff::tz::Offset::from_seconds(self.offset_minutes? as i32 * 60).ok()?;
+            offset.to_timestamp(self.to_datetime()).ok()
+        }
+    }
+
+    impl From<DicomDateTime> for civil::DateTime {
+        fn from(value: DicomDateTime) -> Self {
+            value.to_datetime()
+        }
+    }
+
+    impl TryFrom<civil::DateTime> for DicomDateTime {
+        type Error = InvalidDicomDateTime;
+
+        fn try_from(value: civil::DateTime) -> Result<Self, Self::Error> {
+            let date = DicomDate::try_from(value.date())?;
+            Ok(DicomDateTime::new(date, Some(value.time().into())))
+        }
+    }
+}
@@ -0,0 +1,520 @@
+//! Typed request body of `/tools/find`.
+
+use crate::dicom::{Instance, Patient, Series, SeriesStatus, Study};
+use crate::tag::Tag;
+use crate::types::{InstanceId, PatientId, RequestedTags, SeriesId, StudyId};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Builder for the request body of
+/// [`/tools/find`](https://orthanc.uclouvain.be/api/#tag/System/paths/~1tools~1find/post).
+///
+/// The type parameter `I` is the ID type of the resources to find, and `T`
+/// is the type of each item in the response, which follows from the requested
+/// response content:
+///
+/// | Method                           | `T`                                  |
+/// |----------------------------------|--------------------------------------|
+/// | (default)                        | `I`, e.g. [StudyId]                  |
+/// | [FindBuilder::expand]            | e.g. [`Study<Option<()>>`](Study)    |
+/// | [FindBuilder::requested_tags]    | e.g. [`Study<R>`](Study)             |
+/// | [FindBuilder::response_content]  | e.g. [`FoundResource<StudyId, R>`]   |
+///
+/// ## Example
+///
+/// ```
+/// use orthanc_api::{DicomDate, DicomRange, FindBuilder, LabelsConstraint, tags};
+///
+/// let start: DicomDate = "20250101".parse().unwrap();
+/// let request = FindBuilder::studies()
+///     .query(tags::PATIENT_NAME, "DOE^*")
+///     .query(tags::STUDY_DATE, DicomRange::Since(start))
+///     .query_any(tags::MODALITIES_IN_STUDY, ["CT", "MR"])
+///     .labels(["research"])
+///     .labels_constraint(LabelsConstraint::None)
+///     .limit(10);
+/// let body = serde_json::to_value(&request).unwrap();
+/// assert_eq!(body["Level"], "Study");
+/// assert_eq!(body["Query"]["0008,0020"], "20250101-");
+/// assert_eq!(body["Query"]["0008,0061"], "CT\\MR");
+/// ```
+pub struct FindBuilder<I, T> {
+    request: FindRequest,
+    _item: PhantomData<fn() -> (I, T)>,
+}
+
+impl FindBuilder<PatientId, PatientId> {
+    /// Find patients.
+    pub fn patients() -> Self {
+        Self::new(ResourceLevel::Patient)
+    }
+}
+
+impl FindBuilder<StudyId, StudyId> {
+    /// Find studies.
+    pub fn studies() -> Self {
+        Self::new(ResourceLevel::Study)
+    }
+}
+
+impl FindBuilder<SeriesId, SeriesId> {
+    /// Find series.
+    pub fn series() -> Self {
+        Self::new(ResourceLevel::Series)
+    }
+}
+
+impl FindBuilder<InstanceId, InstanceId> {
+    /// Find instances.
+    pub fn instances() -> Self {
+        Self::new(ResourceLevel::Instance)
+    }
+}
+
+impl<I> FindBuilder<I, I> {
+    fn new(level: ResourceLevel) -> Self {
+        Self {
+            request: FindRequest::new(level),
+            _item: PhantomData,
+        }
+    }
+
+    /// Respond with the full details of each resource, i.e. the same
+    /// content as `GET /{level}/{id}`.
+    pub fn expand(self) -> FindBuilder<I, <I as FindItem>::WithTags<Option<()>>>
+    where
+        I: FindItem,
+    {
+        let mut request = self.request;
+        request.expand = true;
+        FindBuilder::with(request)
+    }
+}
+
+impl<I, T> FindBuilder<I, T> {
+    fn with(request: FindRequest) -> Self {
+        Self {
+            request,
+            _item: PhantomData,
+        }
+    }
+
+    /// Constrain the value of a DICOM tag.
+    ///
+    /// The value can be anything which is formatted as a DICOM query, e.g.
+    /// a string with the wildcards `*` and `?`, a [DicomDate](crate::DicomDate)
+    /// or a [DicomRange](crate::DicomRange).
+    pub fn query(mut self, tag: Tag, value: impl fmt::Display) -> Self {
+        self.request.query.insert(tag, value.to_string());
+        self
+    }
+
+    /// Constrain the value of a DICOM tag to match any of the given values.
+    pub fn query_any<V: fmt::Display>(
+        mut self,
+        tag: Tag,
+        values: impl IntoIterator<Item = V>,
+    ) -> Self {
+        let values: Vec<_> = values.into_iter().map(|v| v.to_string()).collect();
+        self.request.query.insert(tag, values.join("\\"));
+        self
+    }
+
+    /// Constrain the labels of resources, see [FindBuilder::labels_constraint].
+    ///
+    /// **Requires Orthanc 1.12.0** or later.
+    pub fn labels<S: Into<String>>(mut self, labels: impl IntoIterator<Item = S>) -> Self {
+        self.request
+            .labels
+            .extend(labels.into_iter().map(Into::into));
+        self
+    }
+
+    /// How [FindBuilder::labels] are matched. Orthanc's default is [LabelsConstraint::All].
+    pub fn labels_constraint(mut self, constraint: LabelsConstraint) -> Self {
+        self.request.labels_constraint = Some(constraint);
+        self
+    }
+
+    /// Only find resources of the given patient.
+    ///
+    /// **Requires Orthanc 1.12.5** or later.
+    pub fn parent_patient(mut self, id: PatientId) -> Self {
+        self.request.parent_patient = Some(id);
+        self
+    }
+
+    /// Only find resources of the given study.
+    ///
+    /// **Requires Orthanc 1.12.5** or later.
+    pub fn parent_study(mut self, id: StudyId) -> Self {
+        self.request.parent_study = Some(id);
+        self
+    }
+
+    /// Only find resources of the given series.
+    ///
+    /// **Requires Orthanc 1.12.5** or later.
+    pub fn parent_series(mut self, id: SeriesId) -> Self {
+        self.request.parent_series = Some(id);
+        self
+    }
+
+    /// Sort the results. Calling this method again adds a secondary sort key.
+    ///
+    /// **Requires Orthanc 1.12.5** or later.
+    pub fn order_by(mut self, order_by: OrderBy) -> Self {
+        self.request.order_by.push(order_by);
+        self
+    }
+
+    /// Maximum number of results.
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.request.limit = Some(limit);
+        self
+    }
+
+    /// Skip the first results, for pagination together with [FindBuilder::limit].
+    pub fn since(mut self, since: u64) -> Self {
+        self.request.since = Some(since);
+        self
+    }
+
+    /// Whether matching of person names is case-sensitive.
+    /// Orthanc's default is the `CaseSensitivePN` configuration option.
+    pub fn case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.request.case_sensitive = Some(case_sensitive);
+        self
+    }
+
+    /// Split this request into requests for consecutive pages of at most
+    /// `page_size` results, honoring the `Since` and `Limit` of this request.
+    ///
+    /// Unless `Limit` is set, the iterator is unbounded: the last page is the
+    /// first one which has fewer than `page_size` results.
+    ///
+    /// Panics if `page_size` is zero.
+    pub fn paginate(&self, page_size: u64) -> impl Iterator<Item = Self> + use<I, T> {
+        assert!(page_size > 0, "page size must not be zero");
+        let start = self.request.since.unwrap_or(0);
+        // Orthanc does not limit the number of results if Limit is zero
+        let end = self
+            .request
+            .limit
+            .filter(|limit| *limit > 0)
+            .map(|limit| start.saturating_add(limit));
+        let request = self.clone();
+        std::iter::successors(Some(start), move |since| since.checked_add(page_size))
+            .take_while(move |since| end.is_none_or(|end| *since < end))
+            .map(move |since| {
+                let limit = end.map_or(page_size, |end| page_size.min(end - since));
+                request.clone().since(since).limit(limit)
+            })
+    }
+
+    /// Respond with `RequestedTags` deserialized as `R`.
+    ///
+    /// Unless [FindBuilder::response_content] is specified, this also implies
+    /// [FindBuilder::expand].
+    pub fn requested_tags<R: RequestedTags>(self) -> FindBuilder<I, T::WithTags<R>>
+    where
+        T: FindItem,
+    {
+        let mut request = self.request;
+        request.requested_tags = R::names().to_vec();
+        if request.response_content.is_empty() {
+            request.expand = true;
+        } else if !request
+            .response_content
+            .contains(&ResponseContent::RequestedTags)
+        {
+            request
+                .response_content
+                .push(ResponseContent::RequestedTags);
+        }
+        FindBuilder::with(request)
+    }
+
+    /// Respond with only the given content for each resource.
+    ///
+    /// **Requires Orthanc 1.12.5** or later.
+    pub fn response_content(
+        self,
+        content: impl IntoIterator<Item = ResponseContent>,
+    ) -> FindBuilder<I, FoundResource<I, T::Tags>>
+    where
+        T: FindItem,
+    {
+        let mut request = self.request;
+        request.expand = false;
+        request.response_content = content.into_iter().collect();
+        if !request.requested_tags.is_empty()
+            && !request
+                .response_content
+                .contains(&ResponseContent::RequestedTags)
+        {
+            request
+                .response_content
+                .push(ResponseContent::RequestedTags);
+        }
+        FindBuilder::with(request)
+    }
+}
+
+impl<I, T> Clone for FindBuilder<I, T> {
+    fn clone(&self) -> Self {
+        Self::with(self.request.clone())
+    }
+}
+
+impl<I, T> fmt::Debug for FindBuilder<I, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.request.fmt(f)
+    }
+}
+
+impl<I, T> Serialize for FindBuilder<I, T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.request.serialize(serializer)
+    }
+}
+
+/// Request body of `/tools/find`, without type information about the response.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+struct FindRequest {
+    level: ResourceLevel,
+    query: BTreeMap<Tag, String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    labels: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    labels_constraint: Option<LabelsConstraint>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_patient: Option<PatientId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_study: Option<StudyId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_series: Option<SeriesId>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    requested_tags: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    response_content: Vec<ResponseContent>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    expand: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    order_by: Vec<OrderBy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    since: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    case_sensitive: Option<bool>,
+}
+
+impl FindRequest {
+    fn new(level: ResourceLevel) -> Self {
+        Self {
+            level,
+            query: Default::default(),
+            labels: Default::default(),
+            labels_constraint: None,
+            parent_patient: None,
+            parent_study: None,
+            parent_series: None,
+            requested_tags: Default::default(),
+            response_content: Default::default(),
+            expand: false,
+            order_by: Default::default(),
+            limit: None,
+            since: None,
+            case_sensitive: None,
+        }
+    }
+}
+
+/// Level of a DICOM resource in Orthanc.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ResourceLevel {
+    Patient,
+    Study,
+    Series,
+    Instance,
+}
+
+impl ResourceLevel {
+    /// Get the API URI for listing all resources of this level, e.g. `"/studies"`.
+    pub fn uri(&self) -> &'static str {
+        match self {
+            ResourceLevel::Patient => "/patients",
+            ResourceLevel::Study => "/studies",
+            ResourceLevel::Series => "/series",
+            ResourceLevel::Instance => "/instances",
+        }
+    }
+}
+
+/// ID of a DICOM resource at a [ResourceLevel].
+pub trait LevelId {
+    const LEVEL: ResourceLevel;
+}
+
+impl LevelId for PatientId {
+    const LEVEL: ResourceLevel = ResourceLevel::Patient;
+}
+
+impl LevelId for StudyId {
+    const LEVEL: ResourceLevel = ResourceLevel::Study;
+}
+
+impl LevelId for SeriesId {
+    const LEVEL: ResourceLevel = ResourceLevel::Series;
+}
+
+impl LevelId for InstanceId {
+    const LEVEL: ResourceLevel = ResourceLevel::Instance;
+}
+
+/// How the labels of a [FindBuilder] are matched.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum LabelsConstraint {
+    /// Resources must have all the labels.
+    All,
+    /// Resources must have at least one of the labels.
+    Any,
+    /// Resources must have none of the labels.
+    None,
+}
+
+/// Content of each resource in the response of a [FindBuilder], see
+/// [FindBuilder::response_content].
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ResponseContent {
+    MainDicomTags,
+    Metadata,
+    Children,
+    Parent,
+    Labels,
+    Status,
+    IsStable,
+    IsProtected,
+    Attachments,
+    RequestedTags,
+}
+
+/// Sort key of a [FindBuilder].
+#[derive(Serialize, Debug, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "PascalCase")]
+pub struct OrderBy {
+    #[serde(flatten)]
+    key: OrderByKey,
+    direction: Direction,
+}
+
+#[derive(Serialize, Debug, Clone, Eq, PartialEq, Hash)]
+#[serde(tag = "Type", content = "Key")]
+enum OrderByKey {
+    DicomTag(Tag),
+    Metadata(String),
+}
+
+impl OrderBy {
+    /// Sort by the value of a DICOM tag.
+    pub fn tag(tag: Tag, direction: Direction) -> Self {
+        Self {
+            key: OrderByKey::DicomTag(tag),
+            direction,
+        }
+    }
+
+    /// Sort by the value of a metadata.
+    pub fn metadata(name: impl Into<String>, direction: Direction) -> Self {
+        Self {
+            key: OrderByKey::Metadata(name.into()),
+            direction,
+        }
+    }
+}
+
+/// Sort direction of [OrderBy].
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Direction {
+    Asc,
+    Desc,
+}
+
+/// A resource in the response of a [FindBuilder] with
+/// [FindBuilder::response_content].
+///
+/// A field is `Some` if and only if its corresponding [ResponseContent]
+/// was requested.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct FoundResource<I, T> {
+    #[serde(rename = "ID")]
+    pub id: I,
+
+    /// [ResponseContent::MainDicomTags]
+    pub main_dicom_tags: Option<serde_json::Map<String, serde_json::Value>>,
+    /// [ResponseContent::Metadata]
+    pub metadata: Option<BTreeMap<String, String>>,
+    /// [ResponseContent::Children], i.e. the IDs of studies, series or instances.
+    #[serde(alias = "Studies", alias = "Series", alias = "Instances")]
+    pub children: Option<Vec<String>>,
+    /// [ResponseContent::Parent], i.e. the ID of the parent patient, study or series.
+    #[serde(alias = "ParentPatient", alias = "ParentStudy", alias = "ParentSeries")]
+    pub parent: Option<String>,
+    /// [ResponseContent::Labels]
+    pub labels: Option<Vec<String>>,
+    /// [ResponseContent::Status]
+    pub status: Option<SeriesStatus>,
+    /// [ResponseContent::IsStable]
+    pub is_stable: Option<bool>,
+    /// [ResponseContent::IsProtected]
+    pub is_protected: Option<bool>,
+    /// [ResponseContent::RequestedTags]
+    pub requested_tags: Option<T>,
+
+    /// Other content, e.g. [ResponseContent::Attachments].
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Type of an item in the response of a [FindBuilder], which determines
+/// the item type after calling [FindBuilder::requested_tags].
+pub trait FindItem {
+    /// Type of the requested tags.
+    type Tags;
+    /// This type with requested tags of type `R`.
+    type WithTags<R>;
+}
+
+macro_rules! find_items {
+    ($($id:ty => $resource:ident,)*) => {
+        $(
+            impl FindItem for $id {
+                type Tags = Option<()>;
+                type WithTags<R> = $resource<R>;
+            }
+
+            impl<T> FindItem for $resource<T> {
+                type Tags = T;
+                type WithTags<R> = $resource<R>;
+            }
+        )*
+    };
+}
+
+find_items! {
+    PatientId => Patient,
+    StudyId => Study,
+    SeriesId => Series,
+    InstanceId => Instance,
+}
+
+impl<I, T> FindItem for FoundResource<I, T> {
+    type Tags = T;
+    type WithTags<R> = FoundResource<I, R>;
+}
@@ -1,7 +1,8 @@
 use compact_str::CompactString;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-use crate::{JobId, PatientId, SeriesId, StudyId};
+use crate::{JobId, PatientId, SeriesId, StudyId, Tag, Timestamp, tags};
 
 /// Orthanc job detail response from
 /// [`/jobs/{id}`](orthanc.uclouvain.be/api/#tag/Jobs/paths/~1jobs~1{id}/get)
@@ -10,10 +11,12 @@ use crate::{JobId, PatientId, SeriesId, StudyId};
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "PascalCase")]
 pub struct JobInfo {
-    pub completion_time: String,
+    /// Time when the job finished. [None] if it is still pending or running.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_time: Option<Timestamp>,
     #[serde(flatten)]
     pub content: JobContent,
-    pub creation_time: String,
+    pub creation_time: Timestamp,
     pub effective_runtime: f64,
     pub error_code: i32,
     pub error_description: String,
@@ -23,7 +26,8 @@ pub struct JobInfo {
     pub priority: i32,
     pub progress: u8,
     pub state: JobState,
-    pub timestamp: String,
+    /// Time when the details of the job were reported.
+    pub timestamp: Timestamp,
 }
 
 /// Orthanc job state.
@@ -50,13 +54,46 @@ pub enum JobState {
 
 /// The content of an Orthanc job.
 ///
-/// **WARNING**: not all variants are implemented, check the source code.
+/// Jobs of types which are not known, e.g. jobs created by plugins, are
+/// [JobContent::Unknown]. A known job type with unexpected content is an error.
 ///
 /// Job type ref: <https://orthanc.uclouvain.be/hg/orthanc/file/Orthanc-1.12.8/OrthancServer/Sources/ServerJobs/OrthancJobUnserializer.cpp#l66>
 #[serde_with::serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-#[serde(tag = "Type", content = "Content")]
+#[serde(remote = "Self", tag = "Type", content = "Content")]
 pub enum JobContent {
+    /// Create a ZIP archive of DICOM resources.
+    #[serde(rename_all = "PascalCase")]
+    Archive {
+        description: CompactString,
+        instances_count: usize,
+        /// size of the archive in bytes
+        #[serde_as(as = "serde_with::DisplayFromStr")]
+        archive_size: u64,
+        /// size of the DICOM files in bytes
+        #[serde_as(as = "serde_with::DisplayFromStr")]
+        uncompressed_size: u64,
+    },
+    /// Create a ZIP archive of DICOM resources with a DICOMDIR.
+    #[serde(rename_all = "PascalCase")]
+    Media {
+        description: CompactString,
+        instances_count: usize,
+        /// size of the archive in bytes
+        #[serde_as(as = "serde_with::DisplayFromStr")]
+        archive_size: u64,
+        /// size of the DICOM files in bytes
+        #[serde_as(as = "serde_with::DisplayFromStr")]
+        uncompressed_size: u64,
+    },
+    /// DICOM GET-SCU job.
+    #[serde(rename_all = "PascalCase")]
+    DicomGetScu {
+        description: CompactString,
+        local_aet: CompactString,
+        query: Vec<MoveScuJobQuery>,
+        remote_aet: CompactString,
+    },
     /// DICOM MOVE-SCU job.
     #[serde(rename_all = "PascalCase")]
     DicomMoveScu {
@@ -66,7 +103,21 @@ pub enum JobContent {
         remote_aet: CompactString,
         target_aet: CompactString,
     },
-    DicomModalityStore {},
+    /// Send DICOM to a modality (C-STORE SCU) job.
+    #[serde(rename_all = "PascalCase")]
+    DicomModalityStore {
+        description: CompactString,
+        failed_instances_count: usize,
+        instances_count: usize,
+        local_aet: CompactString,
+        /// Local resource IDs being sent to the modality.
+        #[serde(default)]
+        parent_resources: Vec<String>,
+        remote_aet: CompactString,
+        /// Transaction UID of storage commitment, if it was requested.
+        #[serde(default, rename = "StorageCommitmentTransactionUID")]
+        storage_commitment_transaction_uid: Option<String>,
+    },
     /// Store DICOM in peer Orthanc job.
     #[serde(rename_all = "PascalCase")]
     OrthancPeerStore {
@@ -87,9 +138,94 @@ pub enum JobContent {
         // https://orthanc.uclouvain.be/bugs/show_bug.cgi?id=250
     },
     ResourceModification(ResourceModificationContent),
-    MergeStudy {},
-    SplitStudy {},
-    StorageCommitmentScp {},
+    /// Merge series into a study.
+    #[serde(rename_all = "PascalCase")]
+    MergeStudy {
+        description: CompactString,
+        failed_instances_count: usize,
+        instances_count: usize,
+        #[serde(default)]
+        target_study: Option<StudyId>,
+    },
+    /// Split series out of a study into a new study.
+    #[serde(rename_all = "PascalCase")]
+    SplitStudy {
+        description: CompactString,
+        failed_instances_count: usize,
+        instances_count: usize,
+        #[serde(default)]
+        target_study: Option<StudyId>,
+        #[serde(default, rename = "TargetStudyUID")]
+        target_study_uid: Option<String>,
+    },
+    /// Storage commitment SCP job, i.e. answering a storage commitment request of a modality.
+    #[serde(rename_all = "PascalCase")]
+    StorageCommitmentScp {
+        #[serde(default)]
+        called_aet: Option<CompactString>,
+        #[serde(default)]
+        remote_aet: Option<CompactString>,
+        #[serde(default)]
+        transaction_uid: Option<String>,
+    },
+    /// Job of an unknown type. The value is an object with the `"Type"`
+    /// and `"Content"` of the job.
+    #[serde(skip)]
+    Unknown(serde_json::Value),
+}
+
+impl Serialize for JobContent {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            JobContent::Unknown(value) => value.serialize(serializer),
+            _ => JobContent::serialize(self, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for JobContent {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let job_type = value.get("Type").and_then(|t| t.as_str());
+        if job_type.is_some_and(|t| JobContent::KNOWN_TYPES.contains(&t)) {
+            JobContent::deserialize(&value).map_err(serde::de::Error::custom)
+        } else {
+            Ok(JobContent::Unknown(value))
+        }
+    }
+}
+
+impl JobContent {
+    /// Job types which are variants of [JobContent], i.e. not [JobContent::Unknown].
+    const KNOWN_TYPES: &[&str] = &[
+        "Archive",
+        "Media",
+        "DicomGetScu",
+        "DicomMoveScu",
+        "DicomModalityStore",
+        "OrthancPeerStore",
+        "ResourceModification",
+        "MergeStudy",
+        "SplitStudy",
+        "StorageCommitmentScp",
+    ];
+
+    /// Get the job type, e.g. `"DicomMoveScu"`.
+    pub fn job_type(&self) -> &str {
+        match self {
+            JobContent::Archive { .. } => "Archive",
+            JobContent::Media { .. } => "Media",
+            JobContent::DicomGetScu { .. } => "DicomGetScu",
+            JobContent::DicomMoveScu { .. } => "DicomMoveScu",
+            JobContent::DicomModalityStore { .. } => "DicomModalityStore",
+            JobContent::OrthancPeerStore { .. } => "OrthancPeerStore",
+            JobContent::ResourceModification(_) => "ResourceModification",
+            JobContent::MergeStudy { .. } => "MergeStudy",
+            JobContent::SplitStudy { .. } => "SplitStudy",
+            JobContent::StorageCommitmentScp { .. } => "StorageCommitmentScp",
+            JobContent::Unknown(value) => value.get("Type").and_then(|t| t.as_str()).unwrap_or(""),
+        }
+    }
 }
 
 /// Generic resource modification job content.
@@ -117,37 +253,89 @@ pub enum ResourceModificationContent {
     Series(ResourceModification<SeriesId>),
 }
 
-/// The query of a MOVE-SCU job.
+/// The query of a MOVE-SCU job, which is a JSON object of DICOM tags
+/// distinguished by the value of QueryRetrieveLevel.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-#[serde(tag = "0008,0052")]
+#[serde(try_from = "BTreeMap<Tag, String>", into = "BTreeMap<Tag, String>")]
 pub enum MoveScuJobQuery {
-    #[serde(rename = "SERIES")]
     Series {
-        #[serde(rename = "0010,0020")]
         patient_id: CompactString,
-        #[serde(rename = "0008,0050")]
         accession_number: CompactString,
-        #[serde(rename = "0020,000d")]
         study_instance_uid: String,
-        #[serde(rename = "0020,000e")]
         series_instance_uid: String,
     },
-    #[serde(rename = "STUDY")]
     Study {
-        #[serde(rename = "0010,0020")]
         patient_id: CompactString,
-        #[serde(rename = "0008,0050")]
         accession_number: CompactString,
-        #[serde(rename = "0020,000d")]
         study_instance_uid: String,
     },
-    #[serde(rename = "PATIENT")]
     Patient {
-        #[serde(rename = "0010,0020")]
         patient_id: CompactString,
     },
 }
 
+impl TryFrom<BTreeMap<Tag, String>> for MoveScuJobQuery {
+    type Error = String;
+
+    fn try_from(mut value: BTreeMap<Tag, String>) -> Result<Self, Self::Error> {
+        let mut take = |tag: Tag| {
+            value
+                .remove(&tag)
+                .ok_or_else(|| format!("missing {} ({tag})", tag.keyword().unwrap_or_default()))
+        };
+        match take(tags::QUERY_RETRIEVE_LEVEL)?.as_str() {
+            "SERIES" => Ok(Self::Series {
+                patient_id: take(tags::PATIENT_ID)?.into(),
+                accession_number: take(tags::ACCESSION_NUMBER)?.into(),
+                study_instance_uid: take(tags::STUDY_INSTANCE_UID)?,
+                series_instance_uid: take(tags::SERIES_INSTANCE_UID)?,
+            }),
+            "STUDY" => Ok(Self::Study {
+                patient_id: take(tags::PATIENT_ID)?.into(),
+                accession_number: take(tags::ACCESSION_NUMBER)?.into(),
+                study_instance_uid: take(tags::STUDY_INSTANCE_UID)?,
+            }),
+            "PATIENT" => Ok(Self::Patient {
+                patient_id: take(tags::PATIENT_ID)?.into(),
+            }),
+            level => Err(format!("unknown QueryRetrieveLevel {level:?}")),
+        }
+    }
+}
+
+impl From<MoveScuJobQuery> for BTreeMap<Tag, String> {
+    fn from(value: MoveScuJobQuery) -> Self {
+        match value {
+            MoveScuJobQuery::Series {
+                patient_id,
+                accession_number,
+                study_instance_uid,
+                series_instance_uid,
+            } => BTreeMap::from([
+                (tags::QUERY_RETRIEVE_LEVEL, "SERIES".to_string()),
+                (tags::PATIENT_ID, patient_id.into()),
+                (tags::ACCESSION_NUMBER, accession_number.into()),
+                (tags::STUDY_INSTANCE_UID, study_instance_uid),
+                (tags::SERIES_INSTANCE_UID, series_instance_uid),
+            ]),
+            MoveScuJobQuery::Study {
+                patient_id,
+                accession_number,
+                study_instance_uid,
+            } => BTreeMap::from([
+                (tags::QUERY_RETRIEVE_LEVEL, "STUDY".to_string()),
+                (tags::PATIENT_ID, patient_id.into()),
+                (tags::ACCESSION_NUMBER, accession_number.into()),
+                (tags::STUDY_INSTANCE_UID, study_instance_uid),
+            ]),
+            MoveScuJobQuery::Patient { patient_id } => BTreeMap::from([
+                (tags::QUERY_RETRIEVE_LEVEL, "PATIENT".to_string()),
+                (tags::PATIENT_ID, patient_id.into()),
+            ]),
+        }
+    }
+}
+
 impl MoveScuJobQuery {
     /// Get the StudyInstanceUID.
     pub fn study_instance_uid(&self) -> Option<&str> {
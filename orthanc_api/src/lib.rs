@@ -1,11 +1,19 @@
 //! Orthanc API request and response types.
 
 mod dicom;
+mod dicom_datetime;
+mod find;
 mod job;
+mod tag;
+mod timestamp;
 mod types;
 
 pub use crate::dicom::*;
+pub use crate::dicom_datetime::*;
+pub use crate::find::*;
 pub use crate::job::*;
+pub use crate::tag::*;
+pub use crate::timestamp::*;
 pub use crate::types::*;
 
 #[cfg(test)]
@@ -53,6 +61,114 @@ mod tests {
         });
         let actual: <PatientId as ResourceId>::Item = serde_json::from_value(data).unwrap();
         assert_eq!(actual.requested_tags, None);
+        assert_eq!(
+            actual.main_dicom_tags.patient_id.as_deref(),
+            Some("1449c1d")
+        );
+        assert_eq!(
+            actual.studies,
+            vec![StudyId::new("e1df078e-9b2bd072-b24090ad-2ad9c9c9-16c96754")]
+        );
+    }
+
+    #[test]
+    fn test_patient_without_labels() {
+        // Orthanc before 1.12.0 does not have labels
+        let data = json!({
+            "ID": "f966c646-95f12576-9d667375-ca38e459-69a8468b",
+            "IsStable": true,
+            "LastUpdate": "20250704T010750",
+            "MainDicomTags": {},
+            "Studies": [],
+            "Type": "Patient"
+        });
+        let actual: <PatientId as ResourceId>::Item = serde_json::from_value(data).unwrap();
+        assert!(actual.labels.is_empty());
+    }
+
+    #[test]
+    fn test_study() {
+        let data = json!({
+            "ID": "e1df078e-9b2bd072-b24090ad-2ad9c9c9-16c96754",
+            "IsStable": false,
+            "Labels": ["blt"],
+            "LastUpdate": "20250704T010750",
+            "MainDicomTags": {
+                "AccessionNumber": "98edede8b2",
+                "StudyDate": "20130308",
+                "StudyDescription": "MR-Brain w/o Contrast",
+                "StudyID": "98edede8b2",
+                "StudyInstanceUID": "1.2.840.113845.11.1000000001951524609.20200705182951.2689481",
+                "StudyTime": "080626",
+                "TimezoneOffsetFromUTC": "-0500"
+            },
+            "ParentPatient": "f966c646-95f12576-9d667375-ca38e459-69a8468b",
+            "PatientMainDicomTags": {
+                "PatientBirthDate": "20090701",
+                "PatientID": "1449c1d",
+                "PatientName": "anonymized",
+                "PatientSex": "M"
+            },
+            "Series": [
+                "aa8e0a3c-8ddd1186-76a07503-f1b36dbe-c009f45d"
+            ],
+            "Type": "Study"
+        });
+        let actual: Study<Option<()>> = serde_json::from_value(data.clone()).unwrap();
+        assert_eq!(actual.labels, vec!["blt".to_string()]);
+        assert_eq!(
+            actual.main_dicom_tags.study_instance_uid.as_deref(),
+            Some("1.2.840.113845.11.1000000001951524609.20200705182951.2689481")
+        );
+        assert_eq!(
+            actual.main_dicom_tags.other.get("TimezoneOffsetFromUTC"),
+            Some(&json!("-0500"))
+        );
+        assert_eq!(
+            actual.patient_main_dicom_tags.patient_sex.as_deref(),
+            Some("M")
+        );
+        assert_eq!(
+            actual.parent_patient,
+            PatientId::new("f966c646-95f12576-9d667375-ca38e459-69a8468b")
+        );
+        let mut expected = data;
+        expected.as_object_mut().unwrap().remove("Type");
+        expected["RequestedTags"] = json!(null);
+        assert_eq!(serde_json::to_value(actual).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_instance() {
+        let data = json!({
+            "FileSize": 264138,
+            "FileUuid": "4a4c2cb6-6c7c-4d0b-8ab2-08b4a3f83e0c",
+            "ID": "00aee1d3-bdb5e57d-0c229af4-7c3c303c-86bb29a8",
+            "IndexInSeries": 12,
+            "Labels": [],
+            "MainDicomTags": {
+                "AcquisitionNumber": "1",
+                "ImagePositionPatient": "-84.7\\-128.2\\129.8",
+                "InstanceCreationDate": "20130308",
+                "InstanceCreationTime": "081305.609000",
+                "InstanceNumber": "12",
+                "SOPInstanceUID": "1.3.12.2.1107.5.2.19.45152.2013030808130560971102829"
+            },
+            "ParentSeries": "aa8e0a3c-8ddd1186-76a07503-f1b36dbe-c009f45d",
+            "Type": "Instance"
+        });
+        let actual: Instance<Option<()>> = serde_json::from_value(data).unwrap();
+        assert_eq!(actual.file_size, 264138);
+        assert_eq!(actual.index_in_series, Some(12));
+        assert_eq!(
+            actual.main_dicom_tags.instance_number.as_deref(),
+            Some("12")
+        );
+        assert_eq!(actual.main_dicom_tags.other.len(), 1);
+        assert_eq!(
+            actual.parent_series,
+            SeriesId::new("aa8e0a3c-8ddd1186-76a07503-f1b36dbe-c009f45d")
+        );
     }
 
     #[test]
@@ -136,7 +252,11 @@ mod tests {
             "Timestamp": "20250703T002655.833908",
             "Type": "DicomMoveScu"
         });
-        let actual: JobInfo = serde_json::from_value(data).unwrap();
+        let actual: JobInfo = serde_json::from_value(data.clone()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&actual).unwrap()["Content"]["Query"],
+            data["Content"]["Query"]
+        );
         let content = actual.content;
         let expected = JobContent::DicomMoveScu {
             description: CompactString::new("REST API"),
@@ -155,6 +275,27 @@ mod tests {
         assert_eq!(content, expected)
     }
 
+    #[test]
+    fn test_deserialize_move_scu_job_query() {
+        let query: MoveScuJobQuery = serde_json::from_value(json!({
+            "0008,0052": "PATIENT",
+            "0010,0020": "1449c1d"
+        }))
+        .unwrap();
+        assert_eq!(
+            query,
+            MoveScuJobQuery::Patient {
+                patient_id: CompactString::new("1449c1d")
+            }
+        );
+        let error = serde_json::from_value::<MoveScuJobQuery>(json!({
+            "0008,0052": "STUDY",
+            "0010,0020": "1449c1d"
+        }))
+        .unwrap_err();
+        assert_eq!(error.to_string(), "missing AccessionNumber (0008,0050)");
+    }
+
     #[test]
     fn test_deserialize_resource_modification_study() {
         let value = json!({
@@ -199,4 +340,449 @@ mod tests {
             JobContent::ResourceModification(ResourceModificationContent::Study(expected));
         assert_eq!(actual.content, expected_variant)
     }
+
+    fn job_info_json(job_type: &str, content: serde_json::Value) -> serde_json::Value {
+        json!({
+            "CompletionTime": "20250707T134050.679818",
+            "Content": content,
+            "CreationTime": "20250707T134048.977341",
+            "EffectiveRuntime": 1.701,
+            "ErrorCode": 0,
+            "ErrorDescription": "Success",
+            "ErrorDetails": "",
+            "ID": "c304b4ec-43c9-418a-bebd-4f3a648015d5",
+            "Priority": 0,
+            "Progress": 100,
+            "State": "Success",
+            "Timestamp": "20250707T135101.755033",
+            "Type": job_type
+        })
+    }
+
+    #[test]
+    fn test_deserialize_job_modality_store() {
+        let content = json!({
+            "Description": "REST API",
+            "FailedInstancesCount": 0,
+            "InstancesCount": 2,
+            "LocalAet": "DEV",
+            "ParentResources": ["cfd54023-bf099aee-89d171ec-6602b46f-91058a39"],
+            "RemoteAet": "PACS"
+        });
+        let value = job_info_json("DicomModalityStore", content);
+        let actual: JobInfo = serde_json::from_value(value).unwrap();
+        let expected = JobContent::DicomModalityStore {
+            description: CompactString::new("REST API"),
+            failed_instances_count: 0,
+            instances_count: 2,
+            local_aet: CompactString::new("DEV"),
+            parent_resources: vec!["cfd54023-bf099aee-89d171ec-6602b46f-91058a39".to_string()],
+            remote_aet: CompactString::new("PACS"),
+            storage_commitment_transaction_uid: None,
+        };
+        assert_eq!(actual.content, expected);
+        assert_eq!(actual.content.job_type(), "DicomModalityStore");
+    }
+
+    #[test]
+    fn test_deserialize_job_archive() {
+        let content = json!({
+            "ArchiveSize": "1048576",
+            "ArchiveSizeMB": 1,
+            "Description": "REST API",
+            "InstancesCount": 3,
+            "UncompressedSize": "2097152",
+            "UncompressedSizeMB": 2
+        });
+        let value = job_info_json("Archive", content);
+        let actual: JobInfo = serde_json::from_value(value).unwrap();
+        let expected = JobContent::Archive {
+            description: CompactString::new("REST API"),
+            instances_count: 3,
+            archive_size: 1048576,
+            uncompressed_size: 2097152,
+        };
+        assert_eq!(actual.content, expected);
+    }
+
+    #[test]
+    fn test_deserialize_job_unknown_type() {
+        let content = json!({"Resources": ["a", "b"]});
+        let value = job_info_json("DicomWebStowClient", content.clone());
+        let actual: JobInfo = serde_json::from_value(value.clone()).unwrap();
+        let expected = JobContent::Unknown(json!({
+            "Type": "DicomWebStowClient",
+            "Content": content
+        }));
+        assert_eq!(actual.content, expected);
+        assert_eq!(actual.content.job_type(), "DicomWebStowClient");
+        assert_eq!(serde_json::to_value(&actual).unwrap(), value);
+    }
+
+    #[test]
+    fn test_deserialize_job_unexpected_content() {
+        let value = job_info_json("DicomMoveScu", json!({"Description": "REST API"}));
+        let actual = serde_json::from_value::<JobInfo>(value);
+        assert!(actual.unwrap_err().to_string().contains("LocalAet"));
+    }
+
+    #[test]
+    fn test_timestamp() {
+        let actual: Timestamp = "20250703T002648.691947".parse().unwrap();
+        assert_eq!(
+            actual,
+            Timestamp::new(2025, 7, 3, 0, 26, 48, 691947000).unwrap()
+        );
+        assert_eq!(actual.to_string(), "20250703T002648.691947");
+        let without_fraction: Timestamp = "20240229T235959".parse().unwrap();
+        assert_eq!(without_fraction.to_string(), "20240229T235959");
+        assert!(without_fraction < actual);
+        let zero_fraction: Timestamp = "20240229T235959.000000".parse().unwrap();
+        assert_eq!(zero_fraction.to_string(), "20240229T235959.000000");
+        assert_eq!(zero_fraction, without_fraction);
+    }
+
+    #[test]
+    fn test_invalid_timestamp() {
+        for s in [
+            "",
+            "20250703",
+            "20250703T0026",
+            "20250230T002648",
+            "20250703T246048",
+            "2025070XT002648",
+            "20250703T002648.",
+            "20250703T002648.1234567890",
+            "+0250703T002648",
+        ] {
+            assert!(s.parse::<Timestamp>().is_err(), "{s:?} should be invalid");
+        }
+    }
+
+    #[test]
+    fn test_job_info_completion_time() {
+        let content = json!({
+            "Description": "REST API",
+            "FailedInstancesCount": 0,
+            "InstancesCount": 2
+        });
+        let value = job_info_json("MergeStudy", content);
+        let mut value = value.as_object().unwrap().clone();
+        value.remove("CompletionTime");
+        let actual: JobInfo = serde_json::from_value(value.into()).unwrap();
+        assert_eq!(actual.completion_time, None);
+        assert_eq!(actual.creation_time.to_string(), "20250707T134048.977341");
+    }
+
+    #[cfg(feature = "jiff")]
+    #[test]
+    fn test_timestamp_jiff() {
+        let start: Timestamp = "20250703T235959.5".parse().unwrap();
+        let end: Timestamp = "20250704T000001".parse().unwrap();
+        assert_eq!(
+            end.duration_since(start),
+            jiff::SignedDuration::from_millis(1500)
+        );
+        assert_eq!(
+            start.to_jiff_timestamp().to_string(),
+            "2025-07-03T23:59:59.5Z"
+        );
+        let datetime = jiff::civil::DateTime::from(start);
+        assert_eq!(Timestamp::try_from(datetime), Ok(start));
+    }
+
+    #[test]
+    fn test_dicom_date() {
+        for (input, expected) in [
+            ("1/2/1989", "19890102"),
+            ("10/3/1989", "19891003"),
+            ("11/24/1989", "19891124"),
+            ("19891124", "19891124"),
+            ("20240229", "20240229"),
+        ] {
+            let actual: DicomDate = input.parse().unwrap();
+            assert_eq!(actual.to_string(), expected);
+        }
+        for input in [
+            "",
+            "1234567",
+            "text",
+            "20230229",
+            "19891324",
+            "1/2/89",
+            "1/2/3/1989",
+        ] {
+            assert!(
+                input.parse::<DicomDate>().is_err(),
+                "{input:?} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_dicom_time() {
+        for (input, expected) in [
+            ("00", "00"),
+            ("0026", "0026"),
+            ("002648", "002648"),
+            ("002648.5", "002648.500000"),
+            ("002648.691947", "002648.691947"),
+        ] {
+            let actual: DicomTime = input.parse().unwrap();
+            assert_eq!(actual.to_string(), expected);
+        }
+        let actual: DicomTime = "0026".parse().unwrap();
+        assert_eq!(
+            (actual.hour(), actual.minute(), actual.second()),
+            (0, Some(26), None)
+        );
+        for input in [
+            "",
+            "0",
+            "240000",
+            "006000",
+            "0026.5",
+            "002648.1234567",
+            "00:26",
+            "000é0",
+        ] {
+            assert!(
+                input.parse::<DicomTime>().is_err(),
+                "{input:?} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_dicom_date_time() {
+        for input in [
+            "20250703",
+            "2025070300",
+            "20250703002648.691947",
+            "20250703002648-0400",
+            "20250703+0530",
+        ] {
+            let actual: DicomDateTime = input.parse().unwrap();
+            assert_eq!(actual.to_string(), input);
+        }
+        let actual: DicomDateTime = "20250703002648-0430".parse().unwrap();
+        assert_eq!(actual.offset_minutes(), Some(-270));
+        assert_eq!(actual.date(), DicomDate::new(2025, 7, 3).unwrap());
+        for input in [
+            "2025",
+            "202507",
+            "20250703-04",
+            "20250703+1500",
+            "2025070300é",
+            "1/2/2020120000",
+        ] {
+            assert!(
+                input.parse::<DicomDateTime>().is_err(),
+                "{input:?} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_dicom_range() {
+        let start = DicomDate::new(2020, 1, 1).unwrap();
+        let end = DicomDate::new(2020, 12, 31).unwrap();
+        for (input, expected) in [
+            ("20200101", DicomRange::Exact(start)),
+            ("20200101-20201231", DicomRange::Between(start, end)),
+            ("20200101-", DicomRange::Since(start)),
+            ("-20201231", DicomRange::Until(end)),
+        ] {
+            let actual: DicomRange<DicomDate> = serde_json::from_value(json!(input)).unwrap();
+            assert_eq!(actual, expected);
+            assert_eq!(serde_json::to_value(actual).unwrap(), json!(input));
+        }
+        let range = DicomRange::from(start..=end);
+        assert!(range.contains(&DicomDate::new(2020, 6, 15).unwrap()));
+        assert!(!range.contains(&DicomDate::new(2021, 1, 1).unwrap()));
+        for input in ["", "-", "20200101-20201231-", "2020-2021"] {
+            assert!(
+                input.parse::<DicomRange<DicomDate>>().is_err(),
+                "{input:?} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_dicom_date_time_range_with_offset() {
+        let actual: DicomRange<DicomDateTime> = "20250703-0400-20250704-0400".parse().unwrap();
+        let start: DicomDateTime = "20250703-0400".parse().unwrap();
+        let end: DicomDateTime = "20250704-0400".parse().unwrap();
+        assert_eq!(actual, DicomRange::Between(start, end));
+        assert!(
+            "1/2/202012-20200103"
+                .parse::<DicomRange<DicomDateTime>>()
+                .is_err()
+        );
+        let actual: DicomRange<DicomDateTime> = "20250703-0400".parse().unwrap();
+        assert_eq!(actual, DicomRange::Exact(start));
+    }
+
+    #[cfg(feature = "jiff")]
+    #[test]
+    fn test_dicom_date_time_jiff() {
+        let date = DicomDate::new(2025, 7, 3).unwrap();
+        assert_eq!(jiff::civil::Date::from(date), jiff::civil::date(2025, 7, 3));
+        let time: DicomTime = "0026".parse().unwrap();
+        assert_eq!(time.to_time(), jiff::civil::time(0, 26, 0, 0));
+        let dt: DicomDateTime = "20250703002648.5-0400".parse().unwrap();
+        assert_eq!(
+            dt.to_jiff_timestamp().unwrap().to_string(),
+            "2025-07-03T04:26:48.5Z"
+        );
+        let datetime = jiff::civil::date(2025, 7, 3).at(0, 26, 48, 500_000_000);
+        assert_eq!(
+            DicomDateTime::try_from(datetime).unwrap().to_string(),
+            "20250703002648.500000"
+        );
+    }
+
+    #[test]
+    fn test_tag() {
+        let actual: Tag = serde_json::from_value(json!("0020,000D")).unwrap();
+        assert_eq!(actual, tags::STUDY_INSTANCE_UID);
+        assert_eq!(serde_json::to_value(actual).unwrap(), json!("0020,000d"));
+        assert_eq!("PatientID".parse(), Ok(tags::PATIENT_ID));
+        assert!("PatientId".parse::<Tag>().is_err());
+        let private = Tag::new(0x0009, 0x0010);
+        assert!(private.is_private());
+        assert_eq!(private.keyword(), None);
+    }
+
+    #[test]
+    fn test_dictionary() {
+        assert!(dictionary().windows(2).all(|w| w[0].tag < w[1].tag));
+        for entry in dictionary() {
+            assert_eq!(Tag::from_keyword(entry.keyword), Some(entry.tag));
+            assert_eq!(entry.tag.vr(), Some(entry.vr));
+        }
+    }
+
+    #[derive(serde::Deserialize, RequestedTags, Debug, PartialEq)]
+    #[serde(rename_all = "PascalCase")]
+    #[requested_tags(crate = "crate")]
+    struct FindTags {
+        study_description: String,
+    }
+
+    #[test]
+    fn test_find_builder() {
+        let request = FindBuilder::studies()
+            .query(
+                tags::STUDY_DATE,
+                DicomRange::Until(DicomDate::new(2025, 7, 3).unwrap()),
+            )
+            .query_any(tags::MODALITIES_IN_STUDY, ["CT", "MR"])
+            .labels(["a", "b"])
+            .labels_constraint(LabelsConstraint::Any)
+            .parent_patient(PatientId::new(
+                "f966c646-95f12576-9d667375-ca38e459-69a8468b",
+            ))
+            .order_by(OrderBy::tag(tags::STUDY_DATE, Direction::Desc))
+            .order_by(OrderBy::metadata("LastUpdate", Direction::Asc))
+            .case_sensitive(false)
+            .since(20)
+            .limit(10)
+            .requested_tags::<FindTags>();
+        let expected = json!({
+            "Level": "Study",
+            "Query": {
+                "0008,0020": "-20250703",
+                "0008,0061": "CT\\MR"
+            },
+            "Labels": ["a", "b"],
+            "LabelsConstraint": "Any",
+            "ParentPatient": "f966c646-95f12576-9d667375-ca38e459-69a8468b",
+            "RequestedTags": ["StudyDescription"],
+            "Expand": true,
+            "OrderBy": [
+                {"Type": "DicomTag", "Key": "0008,0020", "Direction": "DESC"},
+                {"Type": "Metadata", "Key": "LastUpdate", "Direction": "ASC"}
+            ],
+            "Limit": 10,
+            "Since": 20,
+            "CaseSensitive": false
+        });
+        assert_eq!(serde_json::to_value(&request).unwrap(), expected);
+        let _: FindBuilder<StudyId, Study<FindTags>> = request;
+    }
+
+    #[test]
+    fn test_find_builder_response_content() {
+        let request = FindBuilder::series()
+            .requested_tags::<FindTags>()
+            .response_content([ResponseContent::Parent, ResponseContent::Labels]);
+        let expected = json!({
+            "Level": "Series",
+            "Query": {},
+            "RequestedTags": ["StudyDescription"],
+            "ResponseContent": ["Parent", "Labels", "RequestedTags"]
+        });
+        assert_eq!(serde_json::to_value(&request).unwrap(), expected);
+
+        let data = json!([{
+            "ID": "aa8e0a3c-8ddd1186-76a07503-f1b36dbe-c009f45d",
+            "Type": "Series",
+            "ParentStudy": "e1df078e-9b2bd072-b24090ad-2ad9c9c9-16c96754",
+            "Labels": ["a"],
+            "RequestedTags": {"StudyDescription": "MRI Brain"}
+        }]);
+        let actual: Vec<FoundResource<SeriesId, FindTags>> = serde_json::from_value(data).unwrap();
+        let found = &actual[0];
+        assert_eq!(
+            found.parent.as_deref(),
+            Some("e1df078e-9b2bd072-b24090ad-2ad9c9c9-16c96754")
+        );
+        assert_eq!(found.labels, Some(vec!["a".to_string()]));
+        assert_eq!(found.children, None);
+        assert_eq!(
+            found.requested_tags,
+            Some(FindTags {
+                study_description: "MRI Brain".to_string()
+            })
+        );
+        assert_eq!(found.other.get("Type"), Some(&json!("Series")));
+    }
+
+    #[test]
+    fn test_find_builder_paginate() {
+        let pages = |request: FindBuilder<StudyId, StudyId>| {
+            request
+                .paginate(3)
+                .take(4)
+                .map(|page| {
+                    let body = serde_json::to_value(&page).unwrap();
+                    (
+                        body["Since"].as_u64().unwrap(),
+                        body["Limit"].as_u64().unwrap(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            pages(FindBuilder::studies().since(5).limit(7)),
+            vec![(5, 3), (8, 3), (11, 1)]
+        );
+        assert_eq!(
+            pages(FindBuilder::studies()),
+            vec![(0, 3), (3, 3), (6, 3), (9, 3)]
+        );
+        assert_eq!(
+            pages(FindBuilder::studies().since(1).limit(u64::MAX)),
+            vec![(1, 3), (4, 3), (7, 3), (10, 3)]
+        );
+        assert_eq!(
+            pages(FindBuilder::studies().since(u64::MAX - 4)),
+            vec![(u64::MAX - 4, 3), (u64::MAX - 1, 3)]
+        );
+        assert_eq!(
+            pages(FindBuilder::studies().since(u64::MAX - 4).limit(10)),
+            vec![(u64::MAX - 4, 3), (u64::MAX - 1, 1)]
+        );
+    }
 }
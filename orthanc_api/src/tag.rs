@@ -0,0 +1,222 @@
+//! DICOM tags and data dictionary.
+
+use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::fmt;
+use std::str::FromStr;
+
+/// A DICOM data element tag, e.g. `(0008,0050)` for AccessionNumber.
+///
+/// Tags are written as `"gggg,eeee"` in lowercase hexadecimal, which is how
+/// Orthanc writes tags in its JSON and how it accepts them in queries,
+/// anonymization requests and requested tags. Keywords of the
+/// [DICOM data dictionary](dictionary) are also accepted by [FromStr].
+///
+/// ## Example
+///
+/// ```
+/// use orthanc_api::{Tag, Vr, tags};
+///
+/// let tag: Tag = "0008,0050".parse().unwrap();
+/// assert_eq!(tag, tags::ACCESSION_NUMBER);
+/// assert_eq!(tag.keyword(), Some("AccessionNumber"));
+/// assert_eq!(tag.vr(), Some(Vr::SH));
+/// assert_eq!(Tag::from_keyword("AccessionNumber"), Some(tag));
+/// ```
+#[derive(
+    Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, SerializeDisplay, DeserializeFromStr,
+)]
+pub struct Tag {
+    pub group: u16,
+    pub element: u16,
+}
+
+impl Tag {
+    pub const fn new(group: u16, element: u16) -> Self {
+        Self { group, element }
+    }
+
+    /// Find the tag of a keyword in the DICOM data dictionary.
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        DICTIONARY
+            .iter()
+            .find(|entry| entry.keyword == keyword)
+            .map(|entry| entry.tag)
+    }
+
+    /// Get the entry of this tag in the DICOM data dictionary.
+    pub fn entry(&self) -> Option<&'static DictionaryEntry> {
+        DICTIONARY
+            .binary_search_by_key(self, |entry| entry.tag)
+            .ok()
+            .map(|i| &DICTIONARY[i])
+    }
+
+    /// Get the keyword of this tag, e.g. `"AccessionNumber"`.
+    pub fn keyword(&self) -> Option<&'static str> {
+        self.entry().map(|entry| entry.keyword)
+    }
+
+    /// Get the value representation of this tag.
+    pub fn vr(&self) -> Option<Vr> {
+        self.entry().map(|entry| entry.vr)
+    }
+
+    /// Returns `true` if this is a private tag, i.e. its group is odd.
+    pub fn is_private(&self) -> bool {
+        self.group % 2 == 1
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04x},{:04x}", self.group, self.element)
+    }
+}
+
+impl FromStr for Tag {
+    type Err = InvalidTag;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_tag(s)
+            .or_else(|| Tag::from_keyword(s))
+            .ok_or_else(|| InvalidTag(s.to_string()))
+    }
+}
+
+/// Parse a tag in the format `gggg,eeee`.
+fn parse_tag(s: &str) -> Option<Tag> {
+    let (group, element) = s.split_once(',')?;
+    let parse = |s: &str| {
+        if s.len() == 4 && s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            u16::from_str_radix(s, 16).ok()
+        } else {
+            None
+        }
+    };
+    Some(Tag::new(parse(group)?, parse(element)?))
+}
+
+/// Error parsing a [Tag].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidTag(pub String);
+
+impl fmt::Display for InvalidTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not a DICOM tag or keyword: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidTag {}
+
+macro_rules! value_representations {
+    ($($vr:ident => $description:literal,)*) => {
+        /// DICOM value representation (VR).
+        ///
+        /// Ref: <https://dicom.nema.org/medical/dicom/current/output/chtml/part05/sect_6.2.html>
+        #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+        pub enum Vr {
+            $(
+                #[doc = $description]
+                $vr,
+            )*
+        }
+
+        impl Vr {
+            /// Get the two-letter code of this VR.
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(Vr::$vr => stringify!($vr),)*
+                }
+            }
+        }
+
+        impl FromStr for Vr {
+            type Err = InvalidVr;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $(stringify!($vr) => Ok(Vr::$vr),)*
+                    _ => Err(InvalidVr(s.to_string())),
+                }
+            }
+        }
+    };
+}
+
+value_representations! {
+    AE => "Application Entity",
+    AS => "Age String",
+    AT => "Attribute Tag",
+    CS => "Code String",
+    DA => "Date",
+    DS => "Decimal String",
+    DT => "Date Time",
+    FD => "Floating Point Double",
+    FL => "Floating Point Single",
+    IS => "Integer String",
+    LO => "Long String",
+    LT => "Long Text",
+    OB => "Other Byte",
+    OD => "Other Double",
+    OF => "Other Float",
+    OL => "Other Long",
+    OV => "Other 64-bit Very Long",
+    OW => "Other Word",
+    PN => "Person Name",
+    SH => "Short String",
+    SL => "Signed Long",
+    SQ => "Sequence of Items",
+    SS => "Signed Short",
+    ST => "Short Text",
+    SV => "Signed 64-bit Very Long",
+    TM => "Time",
+    UC => "Unlimited Characters",
+    UI => "Unique Identifier (UID)",
+    UL => "Unsigned Long",
+    UN => "Unknown",
+    UR => "Universal Resource Identifier or Universal Resource Locator (URI/URL)",
+    US => "Unsigned Short",
+    UT => "Unlimited Text",
+    UV => "Unsigned 64-bit Very Long",
+}
+
+impl fmt::Display for Vr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error parsing a [Vr].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidVr(pub String);
+
+impl fmt::Display for InvalidVr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not a DICOM value representation: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidVr {}
+
+/// An entry of the DICOM data dictionary.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct DictionaryEntry {
+    pub tag: Tag,
+    pub vr: Vr,
+    pub keyword: &'static str,
+}
+
+/// Get the entries of the DICOM data dictionary, sorted by tag.
+///
+/// The dictionary is generated from the registries of data elements of
+/// [PS3.6](https://dicom.nema.org/medical/dicom/current/output/chtml/part06/ps3.6.html),
+/// including retired elements. Elements of repeating groups, e.g. `(60xx,3000)`,
+/// are not included. In an Orthanc plugin, tags which are not in this dictionary
+/// (including private tags known to Orthanc) can be looked up at runtime with
+/// [`orthanc_sdk::lookup_dictionary`](https://docs.rs/orthanc_sdk/latest/orthanc_sdk/fn.lookup_dictionary.html).
+pub fn dictionary() -> &'static [DictionaryEntry] {
+    DICTIONARY
+}
+
+orthanc_api_macros::dicom_dictionary!();
@@ -0,0 +1,280 @@
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::fmt;
+use std::str::FromStr;
+
+/// A date-time in the compact ISO 8601 format used by Orthanc's REST API,
+/// e.g. `"20250703T002648.691947"`.
+///
+/// Orthanc writes these timestamps in UTC. Timestamps are ordered chronologically.
+/// With the `jiff` feature, they can be converted to [jiff] types for arithmetic,
+/// e.g. to find studies which have not been updated for 30 days:
+///
+/// ```
+/// # #[cfg(feature = "jiff")]
+/// # {
+/// use orthanc_api::Timestamp;
+///
+/// let last_update: Timestamp = "20250703T002648.691947".parse().unwrap();
+/// let age = jiff::Timestamp::now().duration_since(last_update.to_jiff_timestamp());
+/// let is_old = age > jiff::SignedDuration::from_hours(30 * 24);
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, SerializeDisplay, DeserializeFromStr)]
+pub struct Timestamp {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanosecond: u32,
+    /// Whether the fraction of the second is written by [Display](fmt::Display),
+    /// so that a timestamp without one is written the same way it was parsed.
+    has_fraction: bool,
+}
+
+impl Timestamp {
+    /// Create a [Timestamp], returning [None] if it is not a valid date-time.
+    ///
+    /// The fraction of the second is only written if `nanosecond` is not zero.
+    pub fn new(
+        year: u16,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+        nanosecond: u32,
+    ) -> Option<Self> {
+        let valid = year <= 9999
+            && (1..=12).contains(&month)
+            && (1..=days_in_month(year, month)).contains(&day)
+            && hour < 24
+            && minute < 60
+            && second < 60
+            && nanosecond < 1_000_000_000;
+        valid.then_some(Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            nanosecond,
+            has_fraction: nanosecond != 0,
+        })
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// Fractional part of the second, in nanoseconds.
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+
+    /// The fields which are compared, i.e. everything but [Timestamp::has_fraction].
+    fn key(&self) -> (u16, u8, u8, u8, u8, u8, u32) {
+        (
+            self.year,
+            self.month,
+            self.day,
+            self.hour,
+            self.minute,
+            self.second,
+            self.nanosecond,
+        )
+    }
+}
+
+impl PartialEq for Timestamp {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Timestamp {}
+
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timestamp {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl std::hash::Hash for Timestamp {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
+
+pub(crate) fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Error parsing a [Timestamp].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidTimestamp(pub String);
+
+impl fmt::Display for InvalidTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid Orthanc timestamp: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidTimestamp {}
+
+impl FromStr for Timestamp {
+    type Err = InvalidTimestamp;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s).ok_or_else(|| InvalidTimestamp(s.to_string()))
+    }
+}
+
+/// Parse `YYYYMMDDTHHMMSS` with an optional fraction of up to 9 digits.
+fn parse(s: &str) -> Option<Timestamp> {
+    let (date_time, fraction) = match s.split_once('.') {
+        Some((date_time, fraction)) => (date_time, Some(fraction)),
+        None => (s, None),
+    };
+    let (date, time) = date_time.split_once('T')?;
+    if !date_time.is_ascii() || date.len() != 8 || time.len() != 6 {
+        return None;
+    }
+    let nanosecond = match fraction {
+        Some(f) if (1..=9).contains(&f.len()) => digits(f)? * 10u32.pow(9 - f.len() as u32),
+        Some(_) => return None,
+        None => 0,
+    };
+    let timestamp = Timestamp::new(
+        digits(&date[0..4])? as u16,
+        digits(&date[4..6])? as u8,
+        digits(&date[6..8])? as u8,
+        digits(&time[0..2])? as u8,
+        digits(&time[2..4])? as u8,
+        digits(&time[4..6])? as u8,
+        nanosecond,
+    )?;
+    Some(Timestamp {
+        has_fraction: fraction.is_some(),
+        ..timestamp
+    })
+}
+
+/// Parse a string of ASCII digits (without a sign).
+pub(crate) fn digits(s: &str) -> Option<u32> {
+    if s.bytes().all(|b| b.is_ascii_digit()) {
+        s.parse().ok()
+    } else {
+        None
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}{:02}{:02}T{:02}{:02}{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )?;
+        if !self.has_fraction {
+            Ok(())
+        } else if self.nanosecond.is_multiple_of(1000) {
+            write!(f, ".{:06}", self.nanosecond / 1000)
+        } else {
+            write!(f, ".{:09}", self.nanosecond)
+        }
+    }
+}
+
+#[cfg(feature = "jiff")]
+impl Timestamp {
+    /// Get the date-time as [jiff::civil::DateTime].
+    pub fn to_datetime(&self) -> jiff::civil::DateTime {
+        jiff::civil::datetime(
+            self.year as i16,
+            self.month as i8,
+            self.day as i8,
+            self.hour as i8,
+            self.minute as i8,
+            self.second as i8,
+            self.nanosecond as i32,
+        )
+    }
+
+    /// Get the instant in time, interpreting this date-time as UTC.
+    pub fn to_jiff_timestamp(&self) -> jiff::Timestamp {
+        self.to_datetime()
+            .to_zoned(jiff::tz::TimeZone::UTC)
+            .expect("4-digit years are within the range of jiff::Timestamp")
+            .timestamp()
+    }
+
+    /// Get the duration from `earlier` to this date-time.
+    pub fn duration_since(&self, earlier: Timestamp) -> jiff::SignedDuration {
+        self.to_datetime().duration_since(earlier.to_datetime())
+    }
+}
+
+#[cfg(feature = "jiff")]
+impl From<Timestamp> for jiff::civil::DateTime {
+    fn from(value: Timestamp) -> Self {
+        value.to_datetime()
+    }
+}
+
+#[cfg(feature = "jiff")]
+impl TryFrom<jiff::civil::DateTime> for Timestamp {
+    type Error = InvalidTimestamp;
+
+    fn try_from(value: jiff::civil::DateTime) -> Result<Self, Self::Error> {
+        u16::try_from(value.year())
+            .ok()
+            .and_then(|year| {
+                Timestamp::new(
+                    year,
+                    value.month() as u8,
+                    value.day() as u8,
+                    value.hour() as u8,
+                    value.minute() as u8,
+                    value.second() as u8,
+                    value.subsec_nanosecond() as u32,
+                )
+            })
+            .ok_or_else(|| InvalidTimestamp(value.to_string()))
+    }
+}
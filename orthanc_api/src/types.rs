@@ -180,13 +180,49 @@ where
 
 /// A type for the "RequestedDicomTags" field in Orthanc's JSON response to
 /// getting a DICOM patient, study, series, or instance.
+///
+/// It can be derived from the serde field names of a struct, which are checked
+/// against the DICOM data dictionary at compile time:
+///
+/// ```
+/// use orthanc_api::RequestedTags;
+///
+/// #[derive(serde::Deserialize, RequestedTags)]
+/// #[serde(rename_all = "PascalCase")]
+/// struct StudyTags {
+///     study_description: String,
+///     #[serde(rename = "AccessionNumber")]
+///     accession: String,
+/// }
+///
+/// assert_eq!(StudyTags::names(), &["StudyDescription", "AccessionNumber"]);
+/// ```
+///
+/// A misspelled keyword is a compile error:
+///
+/// ```compile_fail
+/// use orthanc_api::RequestedTags;
+///
+/// #[derive(serde::Deserialize, RequestedTags)]
+/// #[serde(rename_all = "PascalCase")]
+/// struct PatientTags {
+///     patient_id: String, // should be #[serde(rename = "PatientID")]
+/// }
+/// ```
 pub trait RequestedTags {
     /// DICOM tag names of this type.
     fn names() -> &'static [&'static str];
-    // TODO it would be nice to have a derive trait which can figure out the
-    // names from its serde serialized names
 }
 
+/// No requested tags.
+impl RequestedTags for Option<()> {
+    fn names() -> &'static [&'static str] {
+        &[]
+    }
+}
+
+pub use orthanc_api_macros::RequestedTags;
+
 /// Response from deleting a DICOM resource from Orthanc.
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "PascalCase")]
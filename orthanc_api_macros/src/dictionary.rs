@@ -0,0 +1,219 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::collections::HashMap;
+
+/// The DICOM data dictionary, generated from
+/// [PS3.6](https://dicom.nema.org/medical/dicom/current/output/chtml/part06/ps3.6.html)
+/// by the command `just generate-dictionary`.
+const DICTIONARY: &str = include_str!("dictionary.tsv");
+
+/// An entry of the DICOM data dictionary.
+struct Entry {
+    group: u16,
+    element: u16,
+    vr: &'static str,
+    keyword: &'static str,
+}
+
+/// Iterate over the entries of the DICOM data dictionary, sorted by tag.
+fn entries() -> impl Iterator<Item = Entry> {
+    DICTIONARY
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut columns = line.split('\t');
+            let (tag, vr, keyword) = (columns.next(), columns.next(), columns.next());
+            let (group, element) = tag.and_then(parse_tag).expect("invalid tag in dictionary");
+            Entry {
+                group,
+                element,
+                vr: vr.expect("missing VR in dictionary"),
+                keyword: keyword.expect("missing keyword in dictionary"),
+            }
+        })
+}
+
+/// Returns `true` if `name` is a keyword of the DICOM data dictionary.
+pub(crate) fn is_keyword(name: &str) -> bool {
+    entries().any(|entry| entry.keyword == name)
+}
+
+/// Find a keyword which differs from `name` only by case.
+pub(crate) fn suggest(name: &str) -> Option<&'static str> {
+    entries()
+        .map(|entry| entry.keyword)
+        .find(|keyword| keyword.eq_ignore_ascii_case(name))
+}
+
+/// Returns `true` if `name` is a tag in the format `gggg,eeee`.
+pub(crate) fn is_tag(name: &str) -> bool {
+    parse_tag(name).is_some()
+}
+
+/// Parse a tag in the format `gggg,eeee`.
+pub(crate) fn parse_tag(tag: &str) -> Option<(u16, u16)> {
+    let (group, element) = tag.split_once(',')?;
+    let parse = |s: &str| {
+        if s.len() == 4 && s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            u16::from_str_radix(s, 16).ok()
+        } else {
+            None
+        }
+    };
+    Some((parse(group)?, parse(element)?))
+}
+
+/// Check that the tags of `entries` are sorted without duplicates, and that
+/// the names of their constants are unique.
+fn check(entries: &[Entry]) -> Result<(), String> {
+    for w in entries.windows(2) {
+        if (w[0].group, w[0].element) >= (w[1].group, w[1].element) {
+            return Err(format!(
+                "dictionary must be sorted by tag without duplicates: \
+                ({:04X},{:04X}) {} is followed by ({:04X},{:04X}) {}",
+                w[0].group, w[0].element, w[0].keyword, w[1].group, w[1].element, w[1].keyword
+            ));
+        }
+    }
+    let mut names = HashMap::with_capacity(entries.len());
+    for entry in entries {
+        if let Some(other) = names.insert(screaming_snake_case(entry.keyword), entry.keyword) {
+            return Err(format!(
+                "keywords {other} and {} have the same constant name",
+                entry.keyword
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Expand `dicom_dictionary!()`.
+pub(crate) fn expand() -> TokenStream {
+    let entries: Vec<_> = entries().collect();
+    if let Err(e) = check(&entries) {
+        panic!("{e}");
+    }
+    let table = entries.iter().map(|entry| {
+        let Entry {
+            group,
+            element,
+            keyword,
+            ..
+        } = entry;
+        let vr = syn::Ident::new(entry.vr, Span::call_site());
+        quote! {
+            DictionaryEntry { tag: Tag::new(#group, #element), vr: Vr::#vr, keyword: #keyword }
+        }
+    });
+    let constants = entries.iter().map(|entry| {
+        let Entry { group, element, .. } = entry;
+        let name = syn::Ident::new(&screaming_snake_case(entry.keyword), Span::call_site());
+        let doc = format!("{} ({group:04X},{element:04X}) {}", entry.keyword, entry.vr);
+        quote! {
+            #[doc = #doc]
+            pub const #name: Tag = Tag::new(#group, #element);
+        }
+    });
+    quote! {
+        static DICTIONARY: &[DictionaryEntry] = &[#(#table),*];
+
+        /// Constants for the tags of the DICOM data dictionary.
+        pub mod tags {
+            use super::Tag;
+
+            #(#constants)*
+        }
+    }
+}
+
+/// Convert a DICOM keyword to SCREAMING_SNAKE_CASE, keeping acronyms together,
+/// e.g. `"SOPInstanceUID"` becomes `"SOP_INSTANCE_UID"`.
+fn screaming_snake_case(keyword: &str) -> String {
+    let chars: Vec<char> = keyword.chars().collect();
+    let mut name = String::with_capacity(keyword.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if i > 0 && c.is_ascii_uppercase() {
+            let prev = chars[i - 1];
+            let next = chars.get(i + 1).copied();
+            let after_next = chars.get(i + 2).copied();
+            // plural acronym, e.g. "IDs"
+            let is_plural = next == Some('s') && after_next.is_none_or(|c| c.is_ascii_uppercase());
+            let starts_word = !prev.is_ascii_uppercase()
+                || (next.is_some_and(|c| c.is_ascii_lowercase()) && !is_plural);
+            if starts_word {
+                name.push('_');
+            }
+        }
+        name.push(c.to_ascii_uppercase());
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_keyword() {
+        assert!(is_keyword("AccessionNumber"));
+        assert!(!is_keyword("Accession"));
+    }
+
+    #[test]
+    fn test_suggest() {
+        assert_eq!(suggest("PatientId"), Some("PatientID"));
+        assert_eq!(suggest("NotATag"), None);
+    }
+
+    #[test]
+    fn test_is_tag() {
+        assert!(is_tag("0020,000d"));
+        assert!(!is_tag("0020000D"));
+        assert!(!is_tag("0020,00XD"));
+    }
+
+    #[test]
+    fn test_screaming_snake_case() {
+        for (keyword, expected) in [
+            ("AccessionNumber", "ACCESSION_NUMBER"),
+            ("SOPInstanceUID", "SOP_INSTANCE_UID"),
+            ("MRAcquisitionType", "MR_ACQUISITION_TYPE"),
+            ("KVP", "KVP"),
+            ("OtherPatientIDs", "OTHER_PATIENT_IDS"),
+            ("OtherPatientIDsSequence", "OTHER_PATIENT_IDS_SEQUENCE"),
+            ("SOPClassesInStudy", "SOP_CLASSES_IN_STUDY"),
+            ("TimezoneOffsetFromUTC", "TIMEZONE_OFFSET_FROM_UTC"),
+        ] {
+            assert_eq!(screaming_snake_case(keyword), expected);
+        }
+    }
+
+    #[test]
+    fn test_check_dictionary() {
+        let entries: Vec<_> = entries().collect();
+        assert_eq!(check(&entries), Ok(()));
+    }
+
+    #[test]
+    fn test_check_duplicates() {
+        let entry = |group, element, keyword| Entry {
+            group,
+            element,
+            vr: "CS",
+            keyword,
+        };
+        let duplicate_tag = [
+            entry(0x0008, 0x0052, "QueryRetrieveLevel"),
+            entry(0x0008, 0x0052, "QueryRetrieveLevel"),
+        ];
+        assert!(check(&duplicate_tag).unwrap_err().contains("(0008,0052)"));
+        let duplicate_name = [
+            entry(0x0010, 0x1000, "OtherPatientIDs"),
+            entry(0x0010, 0x1002, "OtherPatientIds"),
+        ];
+        assert_eq!(
+            check(&duplicate_name),
+            Err("keywords OtherPatientIDs and OtherPatientIds have the same constant name".into())
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! Implementation details of `orthanc_api`.
+//!
+//! You probably don't want to use this crate directly,
+//! see [orthanc_api](https://crates.io/crates/orthanc_api) instead.
+
+mod dictionary;
+#[cfg(test)]
+mod part06;
+mod requested_tags;
+
+use proc_macro::TokenStream;
+
+/// Derive `RequestedTags` from the field names of a struct.
+///
+/// The names are the field names as deserialized by serde, i.e. the
+/// `#[serde(rename = "...")]` and `#[serde(rename_all = "...")]` attributes
+/// are honored. Each name must be a keyword of the DICOM data dictionary or
+/// a tag in the format `"gggg,eeee"`, otherwise compilation fails.
+///
+/// The generated code refers to `::orthanc_api::RequestedTags`. If
+/// `orthanc_api` is not a direct dependency, specify the path of the trait's
+/// module with `#[requested_tags(crate = "orthanc_sdk::api::types")]`.
+#[proc_macro_derive(RequestedTags, attributes(requested_tags))]
+pub fn derive_requested_tags(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    requested_tags::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generate the DICOM data dictionary of `orthanc_api`.
+///
+/// Expands to a `static DICTIONARY: &[DictionaryEntry]` sorted by tag, and a
+/// module `tags` containing a constant for every tag. `Tag`, `Vr` and
+/// `DictionaryEntry` must be in scope.
+#[proc_macro]
+pub fn dicom_dictionary(input: TokenStream) -> TokenStream {
+    if !input.is_empty() {
+        panic!("This macro does not accept any arguments");
+    }
+    dictionary::expand().into()
+}
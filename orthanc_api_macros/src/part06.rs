@@ -0,0 +1,206 @@
+//! Generator of `dictionary.tsv` from the DocBook source of
+//! [PS3.6](https://dicom.nema.org/medical/dicom/current/output/chtml/part06/ps3.6.html).
+//!
+//! Run by the command `just generate-dictionary`.
+
+use crate::dictionary::parse_tag;
+
+/// A row of a registry of data elements in PS3.6.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Row {
+    group: u16,
+    element: u16,
+    vr: String,
+    keyword: String,
+}
+
+/// Parse the registries of data elements of PS3.6, returning the rows sorted by tag.
+/// Returns an error if a tag is listed more than once.
+///
+/// Every row of a table whose first column is a tag, e.g. `(0008,0050)`, is an entry
+/// with the columns tag, name, keyword and VR. Attributes with more than one possible
+/// VR, e.g. `US or SS`, are listed with the first one. Rows without a keyword or
+/// without a VR (e.g. items) and repeating groups, e.g. `(60xx,3000)`, are skipped.
+pub(crate) fn parse(xml: &str) -> Result<Vec<Row>, String> {
+    let mut entries: Vec<_> = elements(xml, "tr")
+        .into_iter()
+        .filter_map(|row| {
+            let cells: Vec<_> = elements(row, "td").into_iter().map(text).collect();
+            let [tag, _name, keyword, vr, ..] = cells.as_slice() else {
+                return None;
+            };
+            let (group, element) = tag
+                .strip_prefix('(')
+                .and_then(|tag| tag.strip_suffix(')'))
+                .and_then(parse_tag)?;
+            let vr = vr.split(" or ").next()?;
+            let is_vr = vr.len() == 2 && vr.bytes().all(|b| b.is_ascii_uppercase());
+            (is_vr && !keyword.is_empty()).then(|| Row {
+                group,
+                element,
+                vr: vr.to_string(),
+                keyword: keyword.replace(char::is_whitespace, ""),
+            })
+        })
+        .collect();
+    entries.sort_by_key(|entry| (entry.group, entry.element));
+    for w in entries.windows(2) {
+        if (w[0].group, w[0].element) == (w[1].group, w[1].element) {
+            return Err(format!(
+                "tag ({:04X},{:04X}) is listed as both {} and {}",
+                w[0].group, w[0].element, w[0].keyword, w[1].keyword
+            ));
+        }
+    }
+    Ok(entries)
+}
+
+/// Get the contents of the (non-nested) XML elements named `name`.
+fn elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let end = format!("</{name}>");
+    xml.split(&format!("<{name}"))
+        .skip(1)
+        .filter_map(|s| {
+            let (attributes, content) = s.split_once('>')?;
+            // e.g. <tr> or <tr valign="top">, but not <trademark>
+            if !(attributes.is_empty() || attributes.starts_with([' ', '\n', '\r', '\t', '/'])) {
+                return None;
+            }
+            if attributes.ends_with('/') {
+                return Some("");
+            }
+            content.split(&end).next()
+        })
+        .collect()
+}
+
+/// Get the text of XML content, without markup, zero-width spaces and surrounding whitespace.
+fn text(content: &str) -> String {
+    let mut text = String::with_capacity(content.len());
+    let mut in_markup = false;
+    for c in content.chars() {
+        match c {
+            '<' => in_markup = true,
+            '>' => in_markup = false,
+            '\u{200B}' => (),
+            c if !in_markup => text.push(c),
+            _ => (),
+        }
+    }
+    text.replace("&#8203;", "")
+        .replace("&#x200B;", "")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+/// Write the rows as `dictionary.tsv`.
+fn to_tsv(rows: &[Row]) -> String {
+    let mut tsv = String::from(
+        "# DICOM data dictionary, generated from PS3.6 by `just generate-dictionary`.\n\
+        # Do not edit.\n\
+        #\n\
+        # Columns: tag (group,element in hex), VR, keyword.\n\
+        # Attributes with more than one possible VR are listed with the first one.\n",
+    );
+    for row in rows {
+        tsv += &format!(
+            "{:04X},{:04X}\t{}\t{}\n",
+            row.group, row.element, row.vr, row.keyword
+        );
+    }
+    tsv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let xml = r#"<table xml:id="table_6-1"><thead><tr valign="top">
+            <th><para><emphasis role="bold">Tag</emphasis></para></th>
+            <th><para><emphasis role="bold">Keyword</emphasis></para></th>
+            </tr></thead><tbody>
+            <tr valign="top">
+              <td align="center"><para xml:id="para_1">(0028,0106)</para></td>
+              <td align="left"><para xml:id="para_2">Smallest Image Pixel Value</para></td>
+              <td align="left"><para xml:id="para_3">Smallest&#8203;Image&#8203;Pixel&#8203;Value</para></td>
+              <td align="center"><para xml:id="para_4">US or SS</para></td>
+              <td align="center"><para xml:id="para_5">1</para></td>
+              <td align="center"><para xml:id="para_6"/></td>
+            </tr>
+            <tr valign="top">
+              <td><para><emphasis role="italic">(0008,0040)</emphasis></para></td>
+              <td><para><emphasis role="italic">Data Set Type</emphasis></para></td>
+              <td><para><emphasis role="italic">Data&#x200B;Set&#x200B;Type</emphasis></para></td>
+              <td><para><emphasis role="italic">US</emphasis></para></td>
+              <td><para><emphasis role="italic">1</emphasis></para></td>
+              <td><para><emphasis role="italic">RET</emphasis></para></td>
+            </tr>
+            <tr><td><para>(60xx,3000)</para></td><td><para>Overlay Data</para></td>
+              <td><para>OverlayData</para></td><td><para>OB or OW</para></td></tr>
+            <tr><td><para>(FFFE,E000)</para></td><td><para>Item</para></td>
+              <td><para>Item</para></td><td><para>See Note 2</para></td></tr>
+            <tr><td><para>(0018,9445)</para></td><td><para/></td><td><para/></td><td><para/></td></tr>
+            <tr><td><para>1.2.840.10008.1.1</para></td><td><para>Verification SOP Class</para></td>
+              <td><para>Verification</para></td><td><para>SOP Class</para></td></tr>
+            </tbody></table>"#;
+        let row = |group, element, vr: &str, keyword: &str| Row {
+            group,
+            element,
+            vr: vr.to_string(),
+            keyword: keyword.to_string(),
+        };
+        assert_eq!(
+            parse(xml),
+            Ok(vec![
+                row(0x0008, 0x0040, "US", "DataSetType"),
+                row(0x0028, 0x0106, "US", "SmallestImagePixelValue"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(
+            text("\n<para>Data\u{200B}Set&#8203;Type</para>\n"),
+            "DataSetType"
+        );
+        assert_eq!(text("<para>R&amp;D</para>"), "R&D");
+    }
+
+    #[test]
+    fn test_to_tsv() {
+        let rows = [Row {
+            group: 0x0020,
+            element: 0x000d,
+            vr: "UI".to_string(),
+            keyword: "StudyInstanceUID".to_string(),
+        }];
+        assert!(to_tsv(&rows).ends_with("\n0020,000D\tUI\tStudyInstanceUID\n"));
+    }
+
+    #[test]
+    fn test_parse_duplicate_tag() {
+        let xml = "<tr><td>(0008,0052)</td><td/><td>QueryRetrieveLevel</td><td>CS</td></tr>\
+            <tr><td>(0008,0052)</td><td/><td>QueryLevel</td><td>CS</td></tr>";
+        assert_eq!(
+            parse(xml),
+            Err("tag (0008,0052) is listed as both QueryRetrieveLevel and QueryLevel".to_string())
+        );
+    }
+
+    /// Generate `dictionary.tsv` from the file at `$PART06_XML`.
+    #[test]
+    #[ignore = "run by `just generate-dictionary`"]
+    fn generate_dictionary() {
+        let path = std::env::var("PART06_XML").expect("PART06_XML must be set");
+        let xml = std::fs::read_to_string(path).unwrap();
+        let rows = parse(&xml).unwrap();
+        let out = concat!(env!("CARGO_MANIFEST_DIR"), "/src/dictionary.tsv");
+        std::fs::write(out, to_tsv(&rows)).unwrap();
+    }
+}
@@ -0,0 +1,319 @@
+use crate::dictionary;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Fields, LitStr};
+
+/// Expand `#[derive(RequestedTags)]`.
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let container = ContainerAttrs::parse(&input)?;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "RequestedTags can only be derived for structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "RequestedTags can only be derived for structs",
+            ));
+        }
+    };
+    let mut names = Vec::with_capacity(fields.len());
+    for field in fields {
+        let attrs = FieldAttrs::parse(field)?;
+        if attrs.skip {
+            continue;
+        }
+        if attrs.flatten {
+            return Err(syn::Error::new(
+                attrs.flatten_span,
+                "#[serde(flatten)] is not supported by RequestedTags",
+            ));
+        }
+        let ident = field.ident.as_ref().unwrap();
+        let (name, span) = match attrs.rename {
+            Some(rename) => (rename.value(), rename.span()),
+            None => {
+                let name = ident.to_string();
+                let name = name.strip_prefix("r#").unwrap_or(&name);
+                (container.rename_all.apply(name), ident.span())
+            }
+        };
+        validate(&name, span)?;
+        names.push(name);
+    }
+
+    let krate = container.krate;
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #krate::RequestedTags for #ident #ty_generics #where_clause {
+            fn names() -> &'static [&'static str] {
+                &[#(#names),*]
+            }
+        }
+    })
+}
+
+/// Check that `name` is a DICOM keyword or tag.
+fn validate(name: &str, span: proc_macro2::Span) -> syn::Result<()> {
+    if dictionary::is_keyword(name) || dictionary::is_tag(name) {
+        return Ok(());
+    }
+    let message = match dictionary::suggest(name) {
+        Some(keyword) => format!(
+            "unknown DICOM keyword \"{name}\", did you mean \"{keyword}\"? \
+            Hint: use #[serde(rename = \"{keyword}\")]"
+        ),
+        None => format!("unknown DICOM keyword \"{name}\""),
+    };
+    Err(syn::Error::new(span, message))
+}
+
+/// Attributes of the struct.
+struct ContainerAttrs {
+    rename_all: RenameRule,
+    krate: TokenStream,
+}
+
+impl ContainerAttrs {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let mut rename_all = RenameRule::None;
+        let mut krate = quote!(::orthanc_api);
+        for attr in &input.attrs {
+            if attr.path().is_ident("serde") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("rename_all") {
+                        rename_all = if meta.input.peek(syn::Token![=]) {
+                            let value: LitStr = meta.value()?.parse()?;
+                            RenameRule::parse(&value)?
+                        } else {
+                            let mut rule = RenameRule::None;
+                            meta.parse_nested_meta(|meta| {
+                                let value: LitStr = meta.value()?.parse()?;
+                                if meta.path.is_ident("deserialize") {
+                                    rule = RenameRule::parse(&value)?;
+                                }
+                                Ok(())
+                            })?;
+                            rule
+                        };
+                        Ok(())
+                    } else {
+                        skip_meta(meta)
+                    }
+                })?;
+            } else if attr.path().is_ident("requested_tags") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("crate") {
+                        let value: LitStr = meta.value()?.parse()?;
+                        let path: syn::Path = value.parse()?;
+                        krate = quote!(#path);
+                        Ok(())
+                    } else {
+                        Err(meta.error("unsupported requested_tags attribute"))
+                    }
+                })?;
+            }
+        }
+        Ok(Self { rename_all, krate })
+    }
+}
+
+/// serde attributes of a field which determine its name.
+struct FieldAttrs {
+    rename: Option<LitStr>,
+    skip: bool,
+    flatten: bool,
+    flatten_span: proc_macro2::Span,
+}
+
+impl FieldAttrs {
+    fn parse(field: &syn::Field) -> syn::Result<Self> {
+        let mut attrs = Self {
+            rename: None,
+            skip: false,
+            flatten: false,
+            flatten_span: field.span(),
+        };
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    if meta.input.peek(syn::Token![=]) {
+                        attrs.rename = Some(meta.value()?.parse()?);
+                    } else {
+                        meta.parse_nested_meta(|meta| {
+                            let value: LitStr = meta.value()?.parse()?;
+                            if meta.path.is_ident("deserialize") {
+                                attrs.rename = Some(value);
+                            }
+                            Ok(())
+                        })?;
+                    }
+                    Ok(())
+                } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_deserializing") {
+                    attrs.skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("flatten") {
+                    attrs.flatten = true;
+                    attrs.flatten_span = meta.path.span();
+                    Ok(())
+                } else {
+                    skip_meta(meta)
+                }
+            })?;
+        }
+        Ok(attrs)
+    }
+}
+
+/// Consume a serde attribute which is not relevant to RequestedTags.
+fn skip_meta(meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<syn::Lit>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        let content;
+        syn::parenthesized!(content in meta.input);
+        content.parse::<TokenStream>()?;
+    }
+    Ok(())
+}
+
+/// Case conversion of `#[serde(rename_all = "...")]`.
+#[derive(Debug, PartialEq)]
+enum RenameRule {
+    None,
+    LowerCase,
+    UpperCase,
+    PascalCase,
+    CamelCase,
+    SnakeCase,
+    ScreamingSnakeCase,
+    KebabCase,
+    ScreamingKebabCase,
+}
+
+impl RenameRule {
+    fn parse(value: &LitStr) -> syn::Result<Self> {
+        match value.value().as_str() {
+            "lowercase" => Ok(Self::LowerCase),
+            "UPPERCASE" => Ok(Self::UpperCase),
+            "PascalCase" => Ok(Self::PascalCase),
+            "camelCase" => Ok(Self::CamelCase),
+            "snake_case" => Ok(Self::SnakeCase),
+            "SCREAMING_SNAKE_CASE" => Ok(Self::ScreamingSnakeCase),
+            "kebab-case" => Ok(Self::KebabCase),
+            "SCREAMING-KEBAB-CASE" => Ok(Self::ScreamingKebabCase),
+            _ => Err(syn::Error::new_spanned(value, "unknown rename rule")),
+        }
+    }
+
+    /// Rename a snake_case field name, the same way as serde.
+    fn apply(&self, field: &str) -> String {
+        match self {
+            Self::None | Self::SnakeCase => field.to_string(),
+            Self::LowerCase => field.to_ascii_lowercase(),
+            Self::UpperCase | Self::ScreamingSnakeCase => field.to_ascii_uppercase(),
+            Self::PascalCase => field
+                .split('_')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                        None => String::new(),
+                    }
+                })
+                .collect(),
+            Self::CamelCase => {
+                let pascal = Self::PascalCase.apply(field);
+                let mut chars = pascal.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => String::new(),
+                }
+            }
+            Self::KebabCase => field.replace('_', "-"),
+            Self::ScreamingKebabCase => field.replace('_', "-").to_ascii_uppercase(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_rule() {
+        let field = "referring_physician_name";
+        assert_eq!(RenameRule::None.apply(field), field);
+        assert_eq!(
+            RenameRule::PascalCase.apply(field),
+            "ReferringPhysicianName"
+        );
+        assert_eq!(RenameRule::CamelCase.apply(field), "referringPhysicianName");
+        assert_eq!(
+            RenameRule::ScreamingKebabCase.apply(field),
+            "REFERRING-PHYSICIAN-NAME"
+        );
+    }
+
+    #[test]
+    fn test_expand() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[serde(rename_all = "PascalCase")]
+            #[requested_tags(crate = "orthanc_sdk::api::types")]
+            struct Tags {
+                study_description: String,
+                #[serde(rename = "PatientID", default)]
+                mrn: Option<String>,
+                #[serde(skip)]
+                other: (),
+            }
+        };
+        let expected = quote! {
+            impl orthanc_sdk::api::types::RequestedTags for Tags {
+                fn names() -> &'static [&'static str] {
+                    &["StudyDescription", "PatientID"]
+                }
+            }
+        };
+        assert_eq!(expand(input).unwrap().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_expand_keywords_of_complete_dictionary() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[serde(rename_all = "PascalCase")]
+            struct Tags {
+                exposure_time: String,
+                convolution_kernel: String,
+            }
+        };
+        let expected = quote! {
+            impl ::orthanc_api::RequestedTags for Tags {
+                fn names() -> &'static [&'static str] {
+                    &["ExposureTime", "ConvolutionKernel"]
+                }
+            }
+        };
+        assert_eq!(expand(input).unwrap().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_expand_unknown_keyword() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[serde(rename_all = "PascalCase")]
+            struct Tags {
+                patient_id: String,
+            }
+        };
+        let error = expand(input).unwrap_err().to_string();
+        assert!(error.contains("did you mean \"PatientID\""), "{error}");
+    }
+}
@@ -1,6 +1,9 @@
+use super::request::RequestBuilder;
 use super::response::{PostJsonResponse, RestResponse};
 use crate::bindings;
-use crate::sdk::{create_empty_buffer, free_memory_buffer, invoke_service};
+use crate::error_code::OrthancErrorCode;
+use crate::http::Method;
+use crate::sdk::{create_empty_buffer, invoke_service};
 use serde::{Deserialize, Serialize};
 use std::ffi::CString;
 
@@ -16,6 +19,17 @@ impl BaseClient {
         Self { context }
     }
 
+    /// Get the Orthanc plugin context of this client.
+    pub(crate) fn context(&self) -> *mut bindings::OrthancPluginContext {
+        self.context
+    }
+
+    /// Build a request to the built-in Orthanc REST API with any method,
+    /// headers and body. See [RequestBuilder].
+    pub fn request(&self, method: Method, uri: impl Into<String>) -> RequestBuilder {
+        RequestBuilder::new(*self, method, uri.into())
+    }
+
     /// Make a GET call to the built-in Orthanc REST API.
     ///
     /// Wrapper for [`OrthancPluginRestApiGet`](https://orthanc.uclouvain.be/sdk/group__Orthanc.html#ga9fdcf0181b1f0a18c5e4c9fa2dd71cc4)
@@ -38,14 +52,15 @@ impl BaseClient {
     /// Make a DELETE call to the built-in Orthanc REST API.
     ///
     /// Wrapper for [`OrthancPluginRestApiDelete`](https://orthanc.uclouvain.be/sdk/group__Orthanc.html#gadd36e54c43f6371c59301b8b257e3eee)
-    pub fn delete(&self, uri: String) -> bindings::OrthancPluginErrorCode {
+    pub fn delete(&self, uri: String) -> Result<(), OrthancErrorCode> {
         let context = self.context;
         let c_uri = CString::new(uri.as_str()).unwrap();
         let service = bindings::_OrthancPluginService__OrthancPluginService_RestApiDelete;
-        unsafe {
+        let code = unsafe {
             let invoker = (*context).InvokeService;
             invoker.unwrap()(context, service, c_uri.as_ptr() as *const std::ffi::c_void)
-        }
+        };
+        OrthancErrorCode::from(code).into_result()
     }
 
     /// Make a DELETE call to the built-in Orthanc REST API and get its JSON response.
@@ -56,69 +71,50 @@ impl BaseClient {
         tracing::warn!(
             "It seems like Orthanc never responds with a body when DELETE is called from a plugin."
         );
-        let context = self.context;
-        let c_uri = CString::new(uri.as_str()).unwrap();
-        let answer_body = create_empty_buffer();
-        let answer_headers = create_empty_buffer();
-        let http_status = Box::new(0u16);
-        let params = bindings::_OrthancPluginCallRestApi {
-            answerBody: answer_body,
-            answerHeaders: answer_headers,
-            httpStatus: Box::into_raw(http_status),
-            method: bindings::OrthancPluginHttpMethod_OrthancPluginHttpMethod_Delete,
-            uri: c_uri.as_ptr(),
-            headersCount: 0,
-            headersKeys: std::ptr::null(),
-            headersValues: std::ptr::null(),
-            body: std::ptr::null(),
-            bodySize: 0,
-            afterPlugins: 0,
-        };
-        let code = invoke_service(
-            context,
-            bindings::_OrthancPluginService__OrthancPluginService_CallRestApi,
-            params,
-        );
-        let http_status = unsafe {
-            // headers not handled at the moment
-            free_memory_buffer(context, answer_headers);
-            Box::from_raw(params.httpStatus)
-        };
-        // dbg!(*http_status);
-        // dbg!(code);
-        // dbg!(unsafe {*answer_body}.size );
-        RestResponse::new(context, uri, code, answer_body).with_status(*http_status)
+        self.request(Method::Delete, uri).send()
     }
 
-    /// Make a POST call to the built-in Orthanc REST API.
+    /// Make a POST call with a JSON body to the built-in Orthanc REST API.
     ///
-    /// Wrapper for [`OrthancPluginRestApiPost`](https://orthanc.uclouvain.be/sdk/group__Orthanc.html#ga03e733e9fb437f98700ba99881c37642)
+    /// The request is made by [BaseClient::request], so an unsuccessful
+    /// response carries the error of Orthanc's REST API, see [RestResponse::api_error].
+    /// **Requires Orthanc 1.9.2** or later.
     pub fn post<'a, D: Deserialize<'a>, B: Serialize>(
         &self,
         uri: String,
         body: B,
     ) -> PostJsonResponse<D> {
-        let body = match serde_json::to_vec(&body) {
-            Ok(body) => body,
-            Err(e) => {
-                return PostJsonResponse::new(uri, Err(e));
-            }
-        };
-        let context = self.context;
-        let c_uri = CString::new(uri.as_str()).unwrap();
-        let target = create_empty_buffer();
-        let params = bindings::_OrthancPluginRestApiPostPut {
-            target,
-            uri: c_uri.as_ptr(),
-            body: body.as_ptr() as *const _,
-            bodySize: body.len() as u32,
-        };
-        let code = invoke_service(
-            context,
-            bindings::_OrthancPluginService__OrthancPluginService_RestApiPost,
-            params,
-        );
-        let res = RestResponse::new(context, uri.clone(), code, target);
-        PostJsonResponse::new(uri, Ok(res))
+        self.json_request(Method::Post, uri, body)
+    }
+
+    /// Make a PUT call with a JSON body to the built-in Orthanc REST API.
+    ///
+    /// Like [BaseClient::post], the request is made by [BaseClient::request].
+    pub fn put<'a, D: Deserialize<'a>, B: Serialize>(
+        &self,
+        uri: String,
+        body: B,
+    ) -> PostJsonResponse<D> {
+        self.json_request(Method::Put, uri, body)
+    }
+
+    /// Make a PUT call with a raw body to the built-in Orthanc REST API.
+    ///
+    /// Like [BaseClient::post], the request is made by [BaseClient::request].
+    pub fn put_raw<'a, D: Deserialize<'a>>(&self, uri: String, body: &[u8]) -> RestResponse<D> {
+        self.request(Method::Put, uri).body(body).send()
+    }
+
+    fn json_request<'a, D: Deserialize<'a>, B: Serialize>(
+        &self,
+        method: Method,
+        uri: String,
+        body: B,
+    ) -> PostJsonResponse<D> {
+        let result = self
+            .request(method, uri.clone())
+            .json(&body)
+            .map(RequestBuilder::send);
+        PostJsonResponse::new(uri, result)
     }
 }
@@ -1,11 +1,14 @@
 use super::client::BaseClient;
-use crate::api::{PostJsonResponse, RestResponse};
+use crate::api::{
+    JsonResponseError, JsonResponseErrorKind, Pages, PostJsonResponse, ResponseErrorCode,
+    RestResponse,
+};
 use crate::openapi::{
     PatientsIdAnonymizePostRequest as AnonymizePostRequest, ToolsFindPostRequest,
 };
 use orthanc_api::{
-    AnonymizableId, DeleteResponse, DicomResource, DicomResourceId, HierarchalResourceId,
-    IdAndPath, JobId, RequestedTags,
+    AnonymizableId, DeleteResponse, DicomResource, DicomResourceId, FindBuilder,
+    HierarchalResourceId, IdAndPath, JobId, LevelId, RequestedTags, ResourceLevel,
 };
 use serde::de::DeserializeOwned;
 
@@ -45,6 +48,46 @@ impl DicomClient {
         self.0.delete_with_response(id.uri())
     }
 
+    /// Set the value of a metadata of a DICOM resource.
+    ///
+    /// Ref: <https://orthanc.uclouvain.be/book/users/rest.html#metadata>
+    pub fn set_metadata<I: HierarchalResourceId>(
+        &self,
+        id: I,
+        name: &str,
+        value: &str,
+    ) -> Result<(), ResponseErrorCode> {
+        let uri = format!("{}/metadata/{name}", id.uri());
+        self.0
+            .put_raw::<()>(uri, value.as_bytes())
+            .check_error_code()
+    }
+
+    /// Add a label to a DICOM resource.
+    ///
+    /// **Requires Orthanc 1.12.0** or later.
+    pub fn add_label<I: HierarchalResourceId>(
+        &self,
+        id: I,
+        label: &str,
+    ) -> Result<(), ResponseErrorCode> {
+        let uri = format!("{}/labels/{label}", id.uri());
+        self.0.put_raw::<()>(uri, &[]).check_error_code()
+    }
+
+    /// Set the content of an attachment of a DICOM resource.
+    ///
+    /// Ref: <https://orthanc.uclouvain.be/book/users/rest.html#attachments>
+    pub fn put_attachment<I: HierarchalResourceId>(
+        &self,
+        id: I,
+        name: &str,
+        data: &[u8],
+    ) -> Result<(), ResponseErrorCode> {
+        let uri = format!("{}/attachments/{name}", id.uri());
+        self.0.put_raw::<()>(uri, data).check_error_code()
+    }
+
     /// Search DICOM content by calling
     /// [`/tools/find`](https://orthanc.uclouvain.be/api/#tag/System/paths/~1tools~1find/post).
     pub fn find<T: DeserializeOwned, R: Find<Item = T>>(
@@ -54,6 +97,75 @@ impl DicomClient {
         self.0.post("/tools/find".to_string(), request.into())
     }
 
+    /// Search DICOM content by calling
+    /// [`/tools/find`](https://orthanc.uclouvain.be/api/#tag/System/paths/~1tools~1find/post)
+    /// with a typed request. The type of each item in the response follows
+    /// from the request, see [FindBuilder].
+    pub fn find_with<I, T: DeserializeOwned>(
+        &self,
+        request: &FindBuilder<I, T>,
+    ) -> PostJsonResponse<Vec<T>> {
+        self.0.post("/tools/find".to_string(), request)
+    }
+
+    /// Lazily iterate over the results of
+    /// [`/tools/find`](https://orthanc.uclouvain.be/api/#tag/System/paths/~1tools~1find/post),
+    /// requesting `page_size` results at a time with `Since` and `Limit`.
+    ///
+    /// The `Since` and `Limit` of the request are honored, see [FindBuilder::paginate].
+    pub fn find_pages<I: 'static, T: DeserializeOwned + 'static>(
+        &self,
+        request: FindBuilder<I, T>,
+        page_size: u64,
+    ) -> Pages<T> {
+        let client = *self;
+        let mut requests = request.paginate(page_size);
+        Pages::new(page_size, move || {
+            requests
+                .next()
+                .map(|request| client.find_with(&request).into_result())
+        })
+    }
+
+    /// Lazily iterate over the IDs of all the resources of a level, e.g.
+    /// [`/studies`](https://orthanc.uclouvain.be/api/#tag/Studies/paths/~1studies/get),
+    /// requesting `page_size` IDs at a time.
+    pub fn list<I: LevelId + DeserializeOwned + 'static>(&self, page_size: u64) -> Pages<I> {
+        self.list_request(I::LEVEL.uri().to_string(), page_size)
+    }
+
+    /// Lazily iterate over the details of all the resources of a level, e.g.
+    /// [`/studies?expand`](https://orthanc.uclouvain.be/api/#tag/Studies/paths/~1studies/get),
+    /// requesting `page_size` resources at a time.
+    ///
+    /// Use `Option<()>` as `T` to not request any tags.
+    pub fn list_expanded<T: RequestedTags, I: LevelId + DicomResourceId<T>>(
+        &self,
+        page_size: u64,
+    ) -> Pages<<I as DicomResourceId<T>>::Item>
+    where
+        <I as DicomResourceId<T>>::Item: DeserializeOwned + 'static,
+    {
+        self.list_request(expanded_uri::<T>(I::LEVEL), page_size)
+    }
+
+    fn list_request<T: DeserializeOwned + 'static>(&self, uri: String, page_size: u64) -> Pages<T> {
+        let client = self.0;
+        let mut since = Some(0);
+        Pages::new(page_size, move || {
+            let uri = page_uri(&uri, since?, page_size);
+            since = since?.checked_add(page_size);
+            let response = client.get(uri);
+            Some(match response.check_error_code() {
+                Ok(()) => response.data(),
+                Err(code) => Err(JsonResponseError {
+                    uri: response.uri.clone(),
+                    kind: JsonResponseErrorKind::Code(code),
+                }),
+            })
+        })
+    }
+
     /// Make a request to anonymize a DICOM resource.
     pub fn anonymize_request<R: DeserializeOwned, I: AnonymizableId>(
         &self,
@@ -74,9 +186,64 @@ impl DicomClient {
     }
 }
 
+/// URI for listing the details of all the resources of a level, with the
+/// tags of `T` (if any).
+fn expanded_uri<T: RequestedTags>(level: ResourceLevel) -> String {
+    let names = T::names();
+    if names.is_empty() {
+        format!("{}?expand", level.uri())
+    } else {
+        format!("{}?expand&requested-tags={}", level.uri(), names.join(";"))
+    }
+}
+
+/// URI for the page of `uri` which starts at `since`.
+fn page_uri(uri: &str, since: u64, page_size: u64) -> String {
+    let separator = if uri.contains('?') { '&' } else { '?' };
+    format!("{uri}{separator}since={since}&limit={page_size}")
+}
+
 /// Parameters for
 /// [`/tools/find`](https://orthanc.uclouvain.be/api/#tag/System/paths/~1tools~1find/post),
 /// which is called by [DicomClient::find].
+///
+/// Prefer [FindBuilder] with [DicomClient::find_with], which supports all the
+/// parameters of `/tools/find` and infers the response type.
 pub trait Find: Into<ToolsFindPostRequest> {
     type Item: DeserializeOwned;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Deserialize, RequestedTags)]
+    #[serde(rename_all = "PascalCase")]
+    #[allow(dead_code)]
+    struct Tags {
+        study_description: String,
+        #[serde(rename = "AccessionNumber")]
+        accession: String,
+    }
+
+    #[test]
+    fn test_expanded_uri() {
+        assert_eq!(
+            expanded_uri::<Tags>(ResourceLevel::Study),
+            "/studies?expand&requested-tags=StudyDescription;AccessionNumber"
+        );
+        assert_eq!(
+            expanded_uri::<Option<()>>(ResourceLevel::Series),
+            "/series?expand"
+        );
+    }
+
+    #[test]
+    fn test_page_uri() {
+        assert_eq!(page_uri("/patients", 0, 100), "/patients?since=0&limit=100");
+        assert_eq!(
+            page_uri("/studies?expand", 200, 100),
+            "/studies?expand&since=200&limit=100"
+        );
+    }
+}
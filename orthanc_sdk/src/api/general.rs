@@ -1,6 +1,7 @@
 use crate::api::RestResponse;
 use crate::api::client::BaseClient;
-use crate::bindings::{self, OrthancPluginContext, OrthancPluginErrorCode};
+use crate::bindings::OrthancPluginContext;
+use crate::error_code::OrthancErrorCode;
 use orthanc_api::ResourceId;
 use serde::de::DeserializeOwned;
 
@@ -26,17 +27,16 @@ impl GeneralClient {
 
     /// Delete an Orthanc resource.
     pub fn delete<I: ResourceId>(&self, id: I) -> Result<(), DeleteError<I>> {
-        let code = self.0.delete(id.uri());
-        if code == bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_Success {
-            Ok(())
-        } else {
-            Err(DeleteError { code, id })
-        }
+        self.0
+            .delete(id.uri())
+            .map_err(|code| DeleteError { code, id })
     }
 }
 
 /// Error deleting an Orthanc resource.
+#[derive(thiserror::Error, Debug)]
+#[error("cannot delete {id:?}: {code}")]
 pub struct DeleteError<I> {
-    pub code: OrthancPluginErrorCode,
+    pub code: OrthancErrorCode,
     pub id: I,
 }
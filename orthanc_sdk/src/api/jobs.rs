@@ -0,0 +1,276 @@
+use super::client::BaseClient;
+use super::response::{JsonResponseError, ResponseError, RestResponse};
+use crate::bindings::OrthancPluginContext;
+use orthanc_api::{JobId, JobInfo, JobState, ResourceId};
+use serde::de::DeserializeOwned;
+use std::time::{Duration, Instant};
+
+/// Interval between the first polls of [JobsClient::wait].
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Maximum interval between polls of [JobsClient::wait].
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Orthanc client for the jobs API.
+///
+/// Ref: <https://orthanc.uclouvain.be/book/users/advanced-rest.html#jobs>
+#[derive(Copy, Clone)]
+pub struct JobsClient(BaseClient);
+
+impl JobsClient {
+    /// Create a client for the plugin.
+    pub fn new(context: *mut OrthancPluginContext) -> Self {
+        Self(BaseClient::new(context))
+    }
+
+    /// List all the jobs known to Orthanc, with their details.
+    pub fn list(&self) -> RestResponse<Vec<JobInfo>> {
+        self.0.get("/jobs?expand".to_string())
+    }
+
+    /// Get the details of a job.
+    pub fn get(&self, id: &JobId) -> RestResponse<JobInfo> {
+        self.0.get(id.uri())
+    }
+
+    /// Pause a job.
+    pub fn pause(&self, id: &JobId) -> Result<(), JsonResponseError<serde_json::Value>> {
+        self.action(id, "pause")
+    }
+
+    /// Resume a paused job.
+    pub fn resume(&self, id: &JobId) -> Result<(), JsonResponseError<serde_json::Value>> {
+        self.action(id, "resume")
+    }
+
+    /// Cancel a job. The job will finish with [JobState::Failure](orthanc_api::JobState::Failure).
+    pub fn cancel(&self, id: &JobId) -> Result<(), JsonResponseError<serde_json::Value>> {
+        self.action(id, "cancel")
+    }
+
+    /// Resubmit a job which has failed or was canceled. The job keeps its ID.
+    pub fn resubmit(&self, id: &JobId) -> Result<(), JsonResponseError<serde_json::Value>> {
+        self.action(id, "resubmit")
+    }
+
+    /// Get an output of a job, e.g. the `"archive"` of a job which creates
+    /// a ZIP archive. Use [RestResponse::bytes] to get non-JSON outputs.
+    pub fn output<D: DeserializeOwned>(&self, id: &JobId, key: &str) -> RestResponse<D> {
+        self.0.get(format!("{}/{key}", id.uri()))
+    }
+
+    /// Wait for a job to finish, polling its details with increasing intervals
+    /// of up to one second.
+    ///
+    /// `on_progress` is called whenever the state or progress of the job changes.
+    /// The details of the job are returned if it finished successfully.
+    ///
+    /// This function blocks the current thread. To be notified of the job
+    /// finishing by OnChange events instead, see [JobWatcher](crate::utils::JobWatcher).
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use orthanc_sdk::api::JobsClient;
+    /// use std::time::Duration;
+    ///
+    /// # let context = std::ptr::null_mut();
+    /// # let job_id = orthanc_sdk::api::types::JobId::new("".to_string());
+    /// let result = JobsClient::new(context).wait(&job_id, Some(Duration::from_secs(60)), |job| {
+    ///     tracing::info!(job = job.id.to_string(), progress = job.progress);
+    /// });
+    /// ```
+    pub fn wait<F: FnMut(&JobInfo)>(
+        &self,
+        id: &JobId,
+        timeout: Option<Duration>,
+        on_progress: F,
+    ) -> Result<JobInfo, JobWaitError> {
+        poll_job(
+            || self.get(id).ok_data().map_err(Box::new),
+            timeout,
+            on_progress,
+        )
+    }
+
+    fn action(&self, id: &JobId, action: &str) -> Result<(), JsonResponseError<serde_json::Value>> {
+        let uri = format!("{}/{action}", id.uri());
+        self.0.post(uri, serde_json::json!({})).ignore_data()
+    }
+}
+
+/// Call `get` until the job finishes or `timeout` has elapsed.
+fn poll_job<G, F>(
+    mut get: G,
+    timeout: Option<Duration>,
+    mut on_progress: F,
+) -> Result<JobInfo, JobWaitError>
+where
+    G: FnMut() -> Result<JobInfo, Box<ResponseError<JobInfo>>>,
+    F: FnMut(&JobInfo),
+{
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let mut interval = MIN_POLL_INTERVAL;
+    let mut last = None;
+    loop {
+        let job = get().map_err(JobWaitError::Response)?;
+        if last != Some((job.state, job.progress)) {
+            last = Some((job.state, job.progress));
+            on_progress(&job);
+        }
+        match job.state {
+            JobState::Success => return Ok(job),
+            JobState::Failure => return Err(JobWaitError::Failure(Box::new(job))),
+            _ => (),
+        }
+        let now = Instant::now();
+        let sleep = match deadline {
+            Some(deadline) if deadline <= now => return Err(JobWaitError::Timeout(Box::new(job))),
+            Some(deadline) => interval.min(deadline - now),
+            None => interval,
+        };
+        std::thread::sleep(sleep);
+        interval = (interval * 2).min(MAX_POLL_INTERVAL);
+    }
+}
+
+/// Error waiting for an Orthanc job to finish with [JobsClient::wait].
+#[derive(thiserror::Error, Debug)]
+pub enum JobWaitError {
+    /// The job finished with [JobState::Failure].
+    #[error("job {} failed: {} {}", .0.id, .0.error_description, .0.error_details)]
+    Failure(Box<JobInfo>),
+    /// The job did not finish before the timeout. Its last known details are included.
+    #[error("timed out waiting for job {} (progress {}%)", .0.id, .0.progress)]
+    Timeout(Box<JobInfo>),
+    /// The details of the job could not be fetched.
+    #[error("cannot get information of job: {0}")]
+    Response(Box<ResponseError<JobInfo>>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{JsonResponseErrorKind, ResponseErrorCode};
+    use crate::bindings;
+    use crate::error_code::OrthancErrorCode;
+
+    fn job_info(state: &str, progress: u8) -> JobInfo {
+        serde_json::from_value(serde_json::json!({
+            "CompletionTime": "20250703T002648.691947",
+            "Content": {
+                "Description": "REST API",
+                "FailedInstancesCount": 0,
+                "InstancesCount": 2
+            },
+            "CreationTime": "20250703T002645.190848",
+            "EffectiveRuntime": 3.5,
+            "ErrorCode": 0,
+            "ErrorDescription": "Success",
+            "ErrorDetails": "",
+            "ID": "0b09cfb2-d5c3-4340-9f96-0ae8812eadfe",
+            "Priority": 0,
+            "Progress": progress,
+            "State": state,
+            "Timestamp": "20250703T002655.833908",
+            "Type": "MergeStudy"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_poll_job_success() {
+        let mut states = vec![
+            job_info("Success", 100),
+            job_info("Running", 50),
+            job_info("Running", 50),
+            job_info("Pending", 0),
+        ];
+        let mut progress = Vec::new();
+        let job = poll_job(
+            || Ok(states.pop().unwrap()),
+            None,
+            |job| progress.push(job.progress),
+        )
+        .unwrap();
+        assert_eq!(job.state, JobState::Success);
+        assert_eq!(progress, vec![0, 50, 100]);
+    }
+
+    #[test]
+    fn test_poll_job_failure() {
+        let actual = poll_job(|| Ok(job_info("Failure", 20)), None, |_| ());
+        assert!(matches!(actual, Err(JobWaitError::Failure(job)) if job.progress == 20));
+    }
+
+    #[test]
+    fn test_poll_job_timeout() {
+        let actual = poll_job(
+            || Ok(job_info("Running", 10)),
+            Some(Duration::from_millis(120)),
+            |_| (),
+        );
+        assert!(matches!(actual, Err(JobWaitError::Timeout(job)) if job.progress == 10));
+    }
+
+    #[test]
+    fn test_display_response_error() {
+        let error = JobWaitError::Response(Box::new(ResponseError::Json(
+            JsonResponseError::no_response("/jobs/abc".to_string()),
+        )));
+        assert_eq!(
+            error.to_string(),
+            "cannot get information of job: Error calling built-in Orthanc API from plugin at uri=/jobs/abc: no response body"
+        );
+    }
+
+    /// Respond to `OrthancPluginCallRestApi` with a 404 error of Orthanc's REST API.
+    unsafe extern "C" fn call_rest_api_not_found(
+        _context: *mut bindings::OrthancPluginContext,
+        service: bindings::_OrthancPluginService,
+        params: *const std::ffi::c_void,
+    ) -> bindings::OrthancPluginErrorCode {
+        static BODY: &[u8] = br#"{
+            "HttpError" : "Not Found",
+            "HttpStatus" : 404,
+            "Message" : "Unknown resource",
+            "OrthancError" : "Unknown resource",
+            "OrthancStatus" : 17
+        }"#;
+        assert_eq!(
+            service,
+            bindings::_OrthancPluginService__OrthancPluginService_CallRestApi
+        );
+        unsafe {
+            let params = &*(params as *const bindings::_OrthancPluginCallRestApi);
+            let uri = std::ffi::CStr::from_ptr(params.uri);
+            assert_eq!(uri.to_str(), Ok("/jobs/missing/cancel"));
+            *params.httpStatus = 404;
+            (*params.answerBody).data = BODY.as_ptr() as *mut _;
+            (*params.answerBody).size = BODY.len() as u32;
+        }
+        OrthancErrorCode::Success.into()
+    }
+
+    unsafe extern "C" fn free_nothing(_: *mut std::ffi::c_void) {}
+
+    #[test]
+    fn test_action_api_error() {
+        let mut context = bindings::OrthancPluginContext {
+            pluginsManager: std::ptr::null_mut(),
+            orthancVersion: std::ptr::null(),
+            Free: Some(free_nothing),
+            InvokeService: Some(call_rest_api_not_found),
+        };
+        let client = JobsClient::new(&mut context);
+        let error = client
+            .cancel(&JobId::new("missing".to_string()))
+            .unwrap_err();
+        let JsonResponseErrorKind::Code(ResponseErrorCode::Api(api_error)) = error.kind else {
+            panic!(
+                "expected an error of Orthanc's REST API, got {:?}",
+                error.kind
+            );
+        };
+        assert_eq!(api_error.code(), OrthancErrorCode::UnknownResource);
+    }
+}
@@ -4,17 +4,24 @@ mod answers;
 mod client;
 mod dicom;
 mod general;
+mod jobs;
 mod modalities;
+mod pages;
 mod peers;
 mod query;
+mod request;
 mod response;
 
 pub use answers::*;
+pub use client::BaseClient;
 pub use dicom::*;
 pub use general::*;
+pub use jobs::{JobWaitError, JobsClient};
 pub use modalities::*;
+pub use pages::Pages;
 pub use peers::*;
 pub use query::*;
+pub use request::RequestBuilder;
 pub use response::*;
 
 pub use orthanc_api as types;
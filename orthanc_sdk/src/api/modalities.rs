@@ -6,6 +6,8 @@ use crate::openapi::{
     ModalitiesIdGetPost200Response, ModalitiesIdMovePostRequest, ModalitiesIdQueryPost200Response,
     ModalitiesIdQueryPostRequest,
 };
+use orthanc_api::tags;
+use serde::Serialize;
 use serde_json::json;
 
 /// Orthanc client for the networking API.
@@ -24,6 +26,18 @@ impl ModalitiesClient {
         response.unwrap()
     }
 
+    /// Create or update the configuration of a DICOM modality.
+    ///
+    /// Ref: <https://orthanc.uclouvain.be/api/#tag/Networking/paths/~1modalities~1{id}/put>
+    pub fn put<M: std::fmt::Display, B: Serialize>(
+        &self,
+        modality: M,
+        config: B,
+    ) -> Result<(), JsonResponseError<serde_json::Value>> {
+        let url = format!("/modalities/{modality}");
+        self.0.put(url, config).ignore_data()
+    }
+
     /// Trigger C-FIND SCU command against the DICOM modality
     /// (i.e. query PACS for DICOM data).
     pub fn query_raw<M: std::fmt::Display>(
@@ -46,7 +60,7 @@ impl ModalitiesClient {
     ) -> Result<Query, JsonResponseError<ModalitiesIdQueryPost200Response>> {
         let request = ModalitiesIdQueryPostRequest {
             level: Some("Study".to_string()),
-            query: Some(json!({"AccessionNumber": accession_number})),
+            query: Some(json!({tags::ACCESSION_NUMBER.to_string(): accession_number})),
             ..Default::default()
         };
         let response = self.query_raw(modality, request);
@@ -74,7 +88,7 @@ impl ModalitiesClient {
     ) -> PostJsonResponse<ModalitiesIdGetPost200Response> {
         let resources = study_uids
             .into_iter()
-            .map(|u| json!({"StudyInstanceUID": u}))
+            .map(|u| json!({tags::STUDY_INSTANCE_UID.to_string(): u}))
             .collect();
         self.c_move(
             modality,
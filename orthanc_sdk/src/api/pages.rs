@@ -0,0 +1,107 @@
+use super::response::JsonResponseError;
+
+/// Result of getting one page of a [Pages] iterator, or [None] if there are no more pages.
+type Page<T> = Option<Result<Vec<T>, JsonResponseError<Vec<T>>>>;
+
+/// A lazy iterator over resources which are fetched from Orthanc's built-in API
+/// one page at a time, so that only one page is held in memory.
+///
+/// Paging stops after the first page which has fewer than `page_size` items,
+/// or after an error (which is returned as the last item).
+///
+/// Pages are requested with `since` and `limit`, so resources which are added
+/// or deleted while iterating may be skipped or returned twice.
+pub struct Pages<T> {
+    fetch: Box<dyn FnMut() -> Page<T>>,
+    page_size: usize,
+    page: std::vec::IntoIter<T>,
+    done: bool,
+}
+
+impl<T> Pages<T> {
+    /// Create an iterator which calls `fetch` to get the next page.
+    pub(crate) fn new(page_size: u64, fetch: impl FnMut() -> Page<T> + 'static) -> Self {
+        assert!(page_size > 0, "page size must not be zero");
+        Self {
+            fetch: Box::new(fetch),
+            page_size: usize::try_from(page_size).unwrap_or(usize::MAX),
+            page: Vec::new().into_iter(),
+            done: false,
+        }
+    }
+}
+
+impl<T> Iterator for Pages<T> {
+    type Item = Result<T, JsonResponseError<Vec<T>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.page.next() {
+                return Some(Ok(item));
+            }
+            if self.done {
+                return None;
+            }
+            match (self.fetch)() {
+                None => self.done = true,
+                Some(Err(e)) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+                Some(Ok(items)) => {
+                    self.done = items.len() < self.page_size;
+                    self.page = items.into_iter();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pages_of(page_size: u64, total: u64) -> (Pages<u64>, std::rc::Rc<std::cell::Cell<u64>>) {
+        let calls = std::rc::Rc::new(std::cell::Cell::new(0));
+        let counter = calls.clone();
+        let pages = Pages::new(page_size, move || {
+            let since = counter.get() * page_size;
+            counter.set(counter.get() + 1);
+            Some(Ok((since..total.min(since + page_size)).collect()))
+        });
+        (pages, calls)
+    }
+
+    #[test]
+    fn test_pages() {
+        let (pages, calls) = pages_of(3, 7);
+        let items: Vec<_> = pages.map(Result::unwrap).collect();
+        assert_eq!(items, (0..7).collect::<Vec<_>>());
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_pages_exact_multiple() {
+        let (pages, calls) = pages_of(3, 6);
+        assert_eq!(pages.count(), 6);
+        assert_eq!(calls.get(), 3, "an empty page should end iteration");
+    }
+
+    #[test]
+    fn test_pages_is_lazy() {
+        let (mut pages, calls) = pages_of(3, 100);
+        assert_eq!(pages.next().unwrap().unwrap(), 0);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_pages_stops_after_error() {
+        let mut pages = Pages::<u64>::new(2, || {
+            Some(Err(JsonResponseError::no_response(
+                "/tools/find".to_string(),
+            )))
+        });
+        assert!(pages.next().unwrap().is_err());
+        assert!(pages.next().is_none());
+    }
+}
@@ -1,8 +1,9 @@
 use crate::{bindings, openapi::PeersIdStorePostRequest as StoreRequest};
 use orthanc_api::{IdAndPath, JobId};
+use serde::Serialize;
 use serde::de::DeserializeOwned;
 
-use super::{PostJsonResponse, client::BaseClient};
+use super::{JsonResponseError, PostJsonResponse, client::BaseClient};
 
 /// Orthanc client for the peers API.
 ///
@@ -20,6 +21,18 @@ impl PeersClient {
         response.unwrap()
     }
 
+    /// Create or update the configuration of an Orthanc peer.
+    ///
+    /// Ref: <https://orthanc.uclouvain.be/api/#tag/Networking/paths/~1peers~1{id}/put>
+    pub fn put<P: std::fmt::Display, B: Serialize>(
+        &self,
+        peer: P,
+        config: B,
+    ) -> Result<(), JsonResponseError<serde_json::Value>> {
+        let url = format!("/peers/{peer}");
+        self.0.put(url, config).ignore_data()
+    }
+
     /// Send DICOM resources stored locally to some remote Orthanc peer.
     pub fn store_request<P: std::fmt::Display, T: DeserializeOwned>(
         &self,
@@ -0,0 +1,178 @@
+use super::client::BaseClient;
+use super::response::RestResponse;
+use crate::bindings;
+use crate::http::Method;
+use crate::sdk::{create_empty_buffer, free_memory_buffer, invoke_service};
+use http::header::{CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::CString;
+
+/// A request to the built-in Orthanc REST API.
+///
+/// Wrapper for [`OrthancPluginCallRestApi`](https://orthanc.uclouvain.be/sdk/group__Orthanc.html),
+/// which supports any HTTP method, request headers, and returns the HTTP status
+/// and headers of the response. **Requires Orthanc 1.9.2** or later.
+///
+/// ## Example
+///
+/// ```no_run
+/// use orthanc_sdk::api::BaseClient;
+/// use orthanc_sdk::http::Method;
+///
+/// # let context = std::ptr::null_mut();
+/// let response = BaseClient::new(context)
+///     .request(Method::Post, "/tools/find")
+///     .after_plugins(true)
+///     .json(&serde_json::json!({"Level": "Study", "Query": {}}))
+///     .unwrap()
+///     .send::<Vec<String>>();
+/// let studies = response.ok_data();
+/// ```
+pub struct RequestBuilder {
+    client: BaseClient,
+    method: Method,
+    uri: String,
+    headers: HeaderMap,
+    body: Vec<u8>,
+    after_plugins: bool,
+}
+
+impl RequestBuilder {
+    pub(crate) fn new(client: BaseClient, method: Method, uri: String) -> Self {
+        Self {
+            client,
+            method,
+            uri,
+            headers: HeaderMap::new(),
+            body: Vec::new(),
+            after_plugins: false,
+        }
+    }
+
+    /// Add a request header.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Add request headers.
+    pub fn headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// If `true`, the routes registered by plugins (including this one) can be
+    /// called. Otherwise, only the routes built into Orthanc are called.
+    pub fn after_plugins(mut self, after_plugins: bool) -> Self {
+        self.after_plugins = after_plugins;
+        self
+    }
+
+    /// Set the raw request body.
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Set the request body to `body` serialized as JSON.
+    pub fn json<B: Serialize>(mut self, body: &B) -> serde_json::Result<Self> {
+        self.body = serde_json::to_vec(body)?;
+        self.headers
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        Ok(self)
+    }
+
+    /// Send the request.
+    pub fn send<'a, D: Deserialize<'a>>(self) -> RestResponse<D> {
+        let context = self.client.context();
+        let c_uri = CString::new(self.uri.as_str()).unwrap();
+        let (keys, values): (Vec<_>, Vec<_>) = self
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                // header names and values never contain nul bytes
+                let key = CString::new(name.as_str()).unwrap();
+                let value = CString::new(value.as_bytes()).unwrap();
+                (key, value)
+            })
+            .unzip();
+        let key_ptrs: Vec<_> = keys.iter().map(|key| key.as_ptr()).collect();
+        let value_ptrs: Vec<_> = values.iter().map(|value| value.as_ptr()).collect();
+        let answer_body = create_empty_buffer();
+        let answer_headers = create_empty_buffer();
+        let mut http_status = 0u16;
+        let params = bindings::_OrthancPluginCallRestApi {
+            answerBody: answer_body,
+            answerHeaders: answer_headers,
+            httpStatus: &mut http_status,
+            method: self.method.into(),
+            uri: c_uri.as_ptr(),
+            headersCount: key_ptrs.len() as u32,
+            headersKeys: key_ptrs.as_ptr(),
+            headersValues: value_ptrs.as_ptr(),
+            body: self.body.as_ptr() as *const _,
+            bodySize: self.body.len() as u32,
+            afterPlugins: self.after_plugins as u8,
+        };
+        let code = invoke_service(
+            context,
+            bindings::_OrthancPluginService__OrthancPluginService_CallRestApi,
+            params,
+        );
+        let headers = unsafe {
+            let headers = read_headers(&*answer_headers);
+            free_memory_buffer(context, answer_headers);
+            drop(Box::from_raw(answer_headers));
+            headers
+        };
+        RestResponse::new(context, self.uri, code, answer_body)
+            .with_status(http_status)
+            .with_headers(headers)
+    }
+}
+
+/// Parse the response headers written by `OrthancPluginCallRestApi`,
+/// which are a JSON object of header names to values.
+///
+/// Headers which are not valid HTTP are ignored.
+fn read_headers(buffer: &bindings::OrthancPluginMemoryBuffer) -> HeaderMap {
+    if buffer.size == 0 {
+        return HeaderMap::new();
+    }
+    let slice =
+        unsafe { std::slice::from_raw_parts(buffer.data as *const u8, buffer.size as usize) };
+    parse_headers(slice)
+}
+
+fn parse_headers(slice: &[u8]) -> HeaderMap {
+    let Ok(map) = serde_json::from_slice::<HashMap<String, String>>(slice) else {
+        return HeaderMap::new();
+    };
+    map.into_iter()
+        .filter_map(|(name, value)| {
+            let name = HeaderName::try_from(name).ok()?;
+            let value = HeaderValue::try_from(value).ok()?;
+            Some((name, value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_headers() {
+        let body = br#"{"Content-Type": "application/json", "ETag": "\"abc\"", "bad header": "x"}"#;
+        let headers = parse_headers(body);
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers[CONTENT_TYPE], "application/json");
+        assert_eq!(headers["etag"], "\"abc\"");
+    }
+
+    #[test]
+    fn test_parse_headers_invalid_json() {
+        assert!(parse_headers(b"not json").is_empty());
+    }
+}
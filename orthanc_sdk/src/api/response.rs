@@ -1,7 +1,9 @@
 use crate::bindings;
+use crate::error_code::OrthancErrorCode;
 use crate::http::Response;
 use crate::sdk::free_memory_buffer;
 use http::StatusCode;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 
@@ -10,13 +12,18 @@ use std::marker::PhantomData;
 /// [OrthancPluginFreeMemoryBuffer](https://orthanc.uclouvain.be/sdk/OrthancCPlugin_8h_source.html#l02241).
 pub struct RestResponse<D> {
     /// Code returned by calling the Orthanc function.
-    pub code: bindings::OrthancPluginErrorCode,
+    pub code: OrthancErrorCode,
     pub uri: String,
     /// HTTP status code.
     ///
     /// NOTE: status is only available when the implementation calls
     /// [OrthancPluginCallRestApi](https://orthanc.uclouvain.be/sdk/OrthancCPlugin_8h_source.html#l09165).
     pub status: Option<u16>,
+    /// HTTP headers of the response.
+    ///
+    /// NOTE: like [RestResponse::status], headers are only available when the
+    /// implementation calls `OrthancPluginCallRestApi`.
+    pub headers: Option<http::HeaderMap>,
     buffer: *mut bindings::OrthancPluginMemoryBuffer,
     context: *mut bindings::OrthancPluginContext,
     phantom: PhantomData<D>,
@@ -30,11 +37,12 @@ impl<D> RestResponse<D> {
         buffer: *mut bindings::OrthancPluginMemoryBuffer,
     ) -> Self {
         Self {
-            code,
+            code: code.into(),
             uri,
             buffer,
             context,
             status: None,
+            headers: None,
             phantom: Default::default(),
         }
     }
@@ -45,10 +53,19 @@ impl<D> RestResponse<D> {
         self
     }
 
+    /// Set the HTTP headers of this response.
+    pub(crate) fn with_headers(mut self, headers: http::HeaderMap) -> Self {
+        self.headers = Some(headers);
+        self
+    }
+
     /// Returns the error code from this response as [Err].
+    ///
+    /// If the response body is an error from Orthanc's REST API, it is
+    /// returned as [ResponseErrorCode::Api].
     pub fn check_error_code(&self) -> Result<(), ResponseErrorCode> {
-        if self.code != bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_Success {
-            Err(ResponseErrorCode::PluginErrorCode(self.code))
+        let result = if let Err(code) = self.code.into_result() {
+            Err(ResponseErrorCode::PluginErrorCode(code))
         } else if let Some(status) = self.status {
             match StatusCode::from_u16(status) {
                 Ok(status) => {
@@ -62,22 +79,129 @@ impl<D> RestResponse<D> {
             }
         } else {
             Ok(())
+        };
+        result.map_err(|e| match self.api_error() {
+            Some(api_error) => ResponseErrorCode::Api(Box::new(api_error)),
+            None => e,
+        })
+    }
+
+    /// Get the error from Orthanc's REST API in the response body, if any.
+    ///
+    /// NOTE: the error body is only available when the implementation calls
+    /// [OrthancPluginCallRestApi](https://orthanc.uclouvain.be/sdk/OrthancCPlugin_8h_source.html#l09165),
+    /// i.e. for requests made by [BaseClient::request](super::BaseClient::request),
+    /// including the POST and PUT calls of [BaseClient](super::BaseClient) and
+    /// of the typed clients. It is always [None] for the wrapper of
+    /// `OrthancPluginRestApiGet` ([BaseClient::get](super::BaseClient::get)),
+    /// because Orthanc does not give plugins the body of an unsuccessful
+    /// response to that call: only [RestResponse::code] is available.
+    pub fn api_error(&self) -> Option<OrthancApiError> {
+        serde_json::from_slice(self.bytes()).ok()
+    }
+
+    /// Borrow the raw response body, without copying it.
+    ///
+    /// Use this for responses which are not JSON, e.g. DICOM files from
+    /// `/instances/{id}/file`, ZIP archives from `/studies/{id}/archive`
+    /// or rendered PNG frames.
+    pub fn bytes(&self) -> &[u8] {
+        let size = unsafe { (*self.buffer).size as usize };
+        if size == 0 {
+            return &[];
+        }
+        unsafe {
+            let data = (*self.buffer).data as *const u8;
+            std::slice::from_raw_parts(data, size)
+        }
+    }
+
+    /// Copy the raw response body.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.bytes().to_vec()
+    }
+
+    /// Borrow the response body as text, e.g. for `text/plain` responses.
+    pub fn text(&self) -> Result<&str, std::str::Utf8Error> {
+        std::str::from_utf8(self.bytes())
+    }
+
+    /// Convenience method to call [RestResponse::check_error_code] before returning
+    /// [RestResponse::bytes].
+    pub fn ok_bytes(&self) -> Result<&[u8], ResponseErrorCode> {
+        self.check_error_code()?;
+        Ok(self.bytes())
+    }
+}
+
+/// Error response body from Orthanc's REST API.
+///
+/// Ref: <https://orthanc.uclouvain.be/book/users/rest.html>
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct OrthancApiError {
+    /// HTTP status reason phrase, e.g. `"Bad Request"`.
+    pub http_error: String,
+    /// HTTP status code.
+    pub http_status: u16,
+    /// Description of [OrthancApiError::orthanc_status].
+    pub message: String,
+    /// Explanation of what went wrong, if Orthanc gave one.
+    #[serde(default)]
+    pub details: Option<String>,
+    /// HTTP method of the request.
+    #[serde(default)]
+    pub method: Option<String>,
+    /// URI of the request.
+    #[serde(default)]
+    pub uri: Option<String>,
+    /// Name of the Orthanc error code.
+    pub orthanc_error: String,
+    /// Orthanc error code. See [OrthancApiError::code].
+    pub orthanc_status: i32,
+}
+
+impl OrthancApiError {
+    /// Get the Orthanc error code.
+    pub fn code(&self) -> OrthancErrorCode {
+        OrthancErrorCode::from(self.orthanc_status as bindings::OrthancPluginErrorCode)
+    }
+}
+
+impl std::fmt::Display for OrthancApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let (Some(method), Some(uri)) = (&self.method, &self.uri) {
+            write!(f, "{method} {uri}: ")?;
+        }
+        write!(
+            f,
+            "{} (HTTP {}, code {})",
+            self.message, self.http_status, self.orthanc_status
+        )?;
+        if let Some(details) = &self.details {
+            write!(f, ": {details}")?;
         }
+        Ok(())
     }
 }
 
+impl std::error::Error for OrthancApiError {}
+
 /// Code denoting error in response from built-in Orthanc API.
 #[derive(thiserror::Error, Debug)]
 pub enum ResponseErrorCode {
     /// `InvokeService` function produced an unsuccessful error code.
-    #[error("unsuccessful call to Orthanc built-in API (code {0})")]
-    PluginErrorCode(bindings::OrthancPluginErrorCode),
+    #[error("unsuccessful call to Orthanc built-in API: {0}")]
+    PluginErrorCode(OrthancErrorCode),
     /// `OrthancPluginCallRestApi` produced an HTTP error status code.
     #[error("error HTTP response from Orthanc built-in API: status {0}")]
     HttpStatus(StatusCode),
     /// `OrthancPluginCallRestApi` produced an invalid HTTP status code.
     #[error("invalid HTTP status code from Orthanc built-in API: {0} ({1:?})")]
     InvalidHttpStatus(u16, http::status::InvalidStatusCode),
+    /// Orthanc's REST API responded with an error.
+    #[error("error response from Orthanc built-in API: {0}")]
+    Api(Box<OrthancApiError>),
 }
 
 /// Error response from builtin Orthanc API.
@@ -95,7 +219,7 @@ impl<D> Drop for RestResponse<D> {
     }
 }
 
-impl<'a, D: Deserialize<'a>> RestResponse<D> {
+impl<D: DeserializeOwned> RestResponse<D> {
     /// Get the data from Orthanc's REST API response, if any.
     ///
     /// Behind the scenes, this method reads from the memory buffer and deserializes it as JSON.
@@ -103,27 +227,17 @@ impl<'a, D: Deserialize<'a>> RestResponse<D> {
     /// # Return Values
     ///
     /// | Value         | Meaning |
-    /// |---------------|------------------------------------------------------------------|
-    /// | `Err(_)`      | JSON deserialization failed (note: `text/plain` not supported)   |
-    /// | `Ok(None)`    | No response from Orthanc (you should check [RestResponse::code]) |
-    /// | `Ok(Some(_))` | Successful response                                              |
+    /// |---------------|-------------------------------------------------------------------------|
+    /// | `Err(_)`      | JSON deserialization failed (see [RestResponse::text] for `text/plain`) |
+    /// | `Ok(None)`    | No response from Orthanc (you should check [RestResponse::code])        |
+    /// | `Ok(Some(_))` | Successful response                                                     |
     pub fn option_data(&self) -> serde_json::Result<Option<D>> {
-        let size = unsafe { (*self.buffer).size as usize };
-        if size == 0 {
-            return Ok(None);
-        }
-        let slice = unsafe {
-            let data = (*self.buffer).data as *const u8;
-            std::slice::from_raw_parts(data, size)
-        };
-        serde_json::from_slice(slice).map(Some)
+        self.option_data_borrowed()
     }
 
     /// Get the data from Orthanc's response.
     pub fn data(&self) -> Result<D, JsonResponseError<D>> {
-        self.option_data()
-            .map_err(|e| JsonResponseError::deserialization(self.uri.clone(), e))?
-            .ok_or_else(|| JsonResponseError::no_response(self.uri.clone()))
+        self.data_borrowed()
     }
 
     /// Returns the value. This function may panic.
@@ -134,8 +248,78 @@ impl<'a, D: Deserialize<'a>> RestResponse<D> {
     /// Convenience method to call [RestResponse::check_error_code] before returning
     /// [RestResponse::data].
     pub fn ok_data(&self) -> Result<D, ResponseError<D>> {
+        self.ok_data_borrowed()
+    }
+}
+
+impl<D> RestResponse<D> {
+    /// Like [RestResponse::option_data], but `D` may borrow from the memory buffer
+    /// (e.g. `&str` fields), avoiding copies. The data cannot outlive this response:
+    ///
+    /// ```compile_fail
+    /// use orthanc_sdk::api::BaseClient;
+    ///
+    /// # let context = std::ptr::null_mut();
+    /// let patients: Vec<&str> = {
+    ///     let response = BaseClient::new(context).get::<Vec<&str>>("/patients".to_string());
+    ///     response.option_data_borrowed().unwrap().unwrap()
+    /// };
+    /// ```
+    pub fn option_data_borrowed<'r>(&'r self) -> serde_json::Result<Option<D>>
+    where
+        D: Deserialize<'r>,
+    {
+        let slice = self.bytes();
+        if slice.is_empty() {
+            return Ok(None);
+        }
+        serde_json::from_slice(slice).map(Some)
+    }
+
+    /// Like [RestResponse::data], but `D` may borrow from the memory buffer.
+    /// See [RestResponse::option_data_borrowed].
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use orthanc_sdk::api::BaseClient;
+    ///
+    /// #[derive(serde::Deserialize, Debug)]
+    /// #[serde(rename_all = "PascalCase")]
+    /// struct Change<'a> {
+    ///     change_type: &'a str,
+    ///     id: &'a str,
+    /// }
+    ///
+    /// #[derive(serde::Deserialize, Debug)]
+    /// #[serde(rename_all = "PascalCase")]
+    /// struct Changes<'a> {
+    ///     #[serde(borrow)]
+    ///     changes: Vec<Change<'a>>,
+    /// }
+    ///
+    /// # let context = std::ptr::null_mut();
+    /// let response = BaseClient::new(context).get::<Changes>("/changes?limit=10000".to_string());
+    /// let changes = response.data_borrowed().unwrap();
+    /// let new_studies = changes.changes.iter().filter(|c| c.change_type == "NewStudy").count();
+    /// ```
+    pub fn data_borrowed<'r>(&'r self) -> Result<D, JsonResponseError<D>>
+    where
+        D: Deserialize<'r>,
+    {
+        self.option_data_borrowed()
+            .map_err(|e| JsonResponseError::deserialization(self.uri.clone(), e))?
+            .ok_or_else(|| JsonResponseError::no_response(self.uri.clone()))
+    }
+
+    /// Like [RestResponse::ok_data], but `D` may borrow from the memory buffer.
+    /// See [RestResponse::option_data_borrowed].
+    pub fn ok_data_borrowed<'r>(&'r self) -> Result<D, ResponseError<D>>
+    where
+        D: Deserialize<'r>,
+    {
         self.check_error_code()?;
-        let data = self.data()?;
+        let data = self.data_borrowed()?;
         Ok(data)
     }
 }
@@ -202,8 +386,10 @@ pub enum JsonResponseErrorKind<T> {
     NoResponse,
     #[error("unexpected JSON value: {0}")]
     UnexpectedJson(serde_json::Value),
-    #[error("bad value: {reason} in data {value}")]
+    #[error("bad value: {reason} in data {value:?}")]
     BadValue { value: T, reason: &'static str },
+    #[error(transparent)]
+    Code(ResponseErrorCode),
 }
 
 impl<T> JsonResponseError<T> {
@@ -259,6 +445,9 @@ impl<T: std::fmt::Debug> JsonResponseError<T> {
                     "bad value: {reason}"
                 );
             }
+            JsonResponseErrorKind::Code(code) => {
+                tracing::error!(uri = self.uri, error = code.to_string());
+            }
         };
     }
 }
@@ -272,7 +461,23 @@ impl<T, R: Serialize> From<JsonResponseError<T>> for Response<R> {
     }
 }
 
-impl<'a, T: Deserialize<'a>> PostJsonResponse<T> {
+impl<T> PostJsonResponse<T> {
+    /// Check for serialization errors and unsuccessful responses, ignoring
+    /// the response body. Useful for calls which respond with an empty body,
+    /// e.g. most `PUT` calls.
+    pub fn ignore_data(self) -> Result<(), JsonResponseError<T>> {
+        let res = self.result.map_err(|e| JsonResponseError {
+            uri: self.uri.clone(),
+            kind: JsonResponseErrorKind::Serialization(e),
+        })?;
+        res.check_error_code().map_err(|code| JsonResponseError {
+            uri: self.uri,
+            kind: JsonResponseErrorKind::Code(code),
+        })
+    }
+}
+
+impl<T: DeserializeOwned> PostJsonResponse<T> {
     /// Apply the given function after handling all serialization+deserialization errors.
     ///
     /// If the given function is to produce [Err], it is to return its parameter along
@@ -288,6 +493,10 @@ impl<'a, T: Deserialize<'a>> PostJsonResponse<T> {
                 uri: self.uri.clone(),
                 kind,
             })?;
+        res.check_error_code().map_err(|code| JsonResponseError {
+            uri: self.uri.clone(),
+            kind: JsonResponseErrorKind::Code(code),
+        })?;
         let possibly = res
             .option_data()
             .map_err(JsonResponseErrorKind::Deserialization)
@@ -320,7 +529,7 @@ impl<'a, T: Deserialize<'a>> PostJsonResponse<T> {
     }
 }
 
-impl<'a, T: std::fmt::Debug + Deserialize<'a>> PostJsonResponse<T> {
+impl<T: std::fmt::Debug + DeserializeOwned> PostJsonResponse<T> {
     /// Return the value as [Ok], and any serialization+deserialization
     /// errors as [Err] with `code` being [StatusCode::INTERNAL_SERVER_ERROR].
     /// Additionally, errors are also reported by [JsonResponseError::trace].
@@ -344,3 +553,135 @@ impl<'a, T: std::fmt::Debug + Deserialize<'a>> PostJsonResponse<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_orthanc_api_error() {
+        let body = r#"{
+            "Details" : "Unknown resource: 1.2.3",
+            "HttpError" : "Not Found",
+            "HttpStatus" : 404,
+            "Message" : "Unknown resource",
+            "Method" : "POST",
+            "OrthancError" : "Unknown resource",
+            "OrthancStatus" : 17,
+            "Uri" : "/studies/1.2.3/anonymize"
+        }"#;
+        let error: OrthancApiError = serde_json::from_str(body).unwrap();
+        assert_eq!(error.http_status, 404);
+        assert_eq!(error.code(), OrthancErrorCode::UnknownResource);
+        assert_eq!(
+            error.to_string(),
+            "POST /studies/1.2.3/anonymize: Unknown resource (HTTP 404, code 17): Unknown resource: 1.2.3"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_orthanc_api_error_without_details() {
+        let body = r#"{
+            "HttpError" : "Bad Request",
+            "HttpStatus" : 400,
+            "Message" : "Bad file format",
+            "OrthancError" : "Bad file format",
+            "OrthancStatus" : 15
+        }"#;
+        let error: OrthancApiError = serde_json::from_str(body).unwrap();
+        assert_eq!(error.details, None);
+        assert_eq!(error.to_string(), "Bad file format (HTTP 400, code 15)");
+    }
+
+    unsafe extern "C" fn free_nothing(_: *mut std::ffi::c_void) {}
+
+    /// Create a [RestResponse] of `body` without calling Orthanc.
+    fn fake_response<D>(
+        context: &mut bindings::OrthancPluginContext,
+        body: &'static [u8],
+    ) -> RestResponse<D> {
+        fake_response_with_code(context, OrthancErrorCode::Success, body)
+    }
+
+    /// Create a [RestResponse] of `code` and `body` without calling Orthanc.
+    fn fake_response_with_code<D>(
+        context: &mut bindings::OrthancPluginContext,
+        code: OrthancErrorCode,
+        body: &'static [u8],
+    ) -> RestResponse<D> {
+        let buffer = Box::new(bindings::OrthancPluginMemoryBuffer {
+            data: body.as_ptr() as *mut _,
+            size: body.len() as u32,
+        });
+        RestResponse::new(
+            context,
+            "/test".to_string(),
+            code.into(),
+            Box::into_raw(buffer),
+        )
+    }
+
+    fn fake_context() -> bindings::OrthancPluginContext {
+        bindings::OrthancPluginContext {
+            pluginsManager: std::ptr::null_mut(),
+            orthancVersion: std::ptr::null(),
+            Free: Some(free_nothing),
+            InvokeService: None,
+        }
+    }
+
+    #[test]
+    fn test_data_borrowed() {
+        let mut context = fake_context();
+        let response: RestResponse<Vec<&str>> = fake_response(&mut context, br#"["a", "b"]"#);
+        let data = response.ok_data_borrowed().unwrap();
+        assert_eq!(data, vec!["a", "b"]);
+        assert_eq!(data[0].as_ptr(), response.bytes()[2..].as_ptr());
+    }
+
+    #[test]
+    fn test_bytes_and_text() {
+        let mut context = fake_context();
+        let response: RestResponse<()> = fake_response(&mut context, b"hello");
+        assert_eq!(response.ok_bytes().unwrap(), b"hello");
+        assert_eq!(response.text().unwrap(), "hello");
+        let empty: RestResponse<()> = fake_response(&mut context, b"");
+        assert!(empty.bytes().is_empty());
+    }
+
+    #[test]
+    fn test_api_error_of_call_rest_api() {
+        let mut context = fake_context();
+        let body = br#"{
+            "HttpError" : "Not Found",
+            "HttpStatus" : 404,
+            "Message" : "Unknown resource",
+            "OrthancError" : "Unknown resource",
+            "OrthancStatus" : 17
+        }"#;
+        let response: RestResponse<()> = fake_response(&mut context, body).with_status(404);
+        assert_eq!(
+            response.api_error().map(|e| e.code()),
+            Some(OrthancErrorCode::UnknownResource)
+        );
+        assert!(matches!(
+            response.check_error_code(),
+            Err(ResponseErrorCode::Api(_))
+        ));
+    }
+
+    #[test]
+    fn test_api_error_of_rest_api_get() {
+        // OrthancPluginRestApiGet only gives an error code, without a body
+        let mut context = fake_context();
+        let response: RestResponse<()> =
+            fake_response_with_code(&mut context, OrthancErrorCode::UnknownResource, b"");
+        assert_eq!(response.api_error(), None);
+        assert!(matches!(
+            response.check_error_code(),
+            Err(ResponseErrorCode::PluginErrorCode(
+                OrthancErrorCode::UnknownResource
+            ))
+        ));
+    }
+}
@@ -4,6 +4,8 @@ use crate::{
     bindings,
     sdk::{free_string, invoke_service},
 };
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
 
 /// Return the content of the configuration file(s).
 ///
@@ -15,6 +17,47 @@ pub fn get_configuration(
     get_configuration_raw(context).map(|buffer| OrthancConfigurationBuffer { context, buffer })
 }
 
+/// Read the section `name` of the Orthanc configuration as `T`.
+///
+/// - If the section is missing, `T` is deserialized from an empty object, so
+///   fields with `#[serde(default)]` get their default values.
+/// - Environment variables of the form `ORTHANC__{SECTION}__{KEY}` override the
+///   values in the configuration file, e.g. `ORTHANC__BLT__LOG_LEVEL=debug` sets
+///   `"Blt": { "LogLevel": "debug" }`. Values which are valid JSON (numbers,
+///   booleans, ...) are parsed as JSON, unless the field is a string, e.g.
+///   `ORTHANC__BLT__AE_TITLE=12345` sets `"AeTitle": "12345"` if `AeTitle` is a string.
+/// - Errors have the precise path of the invalid value, e.g.
+///   `Blt.Peer: invalid type: integer `1`, expected a string`.
+///
+/// ## Example
+///
+/// ```no_run
+/// #[derive(serde::Deserialize)]
+/// #[serde(rename_all = "PascalCase")]
+/// struct MyPluginConfig {
+///     peer: String,
+///     #[serde(default)]
+///     verbose: bool,
+/// }
+///
+/// # let context = std::ptr::null_mut();
+/// let config: MyPluginConfig = match orthanc_sdk::get_plugin_config(context, "MyPlugin") {
+///     Ok(config) => config,
+///     Err(e) => {
+///         tracing::error!("{e}");
+///         return;
+///     }
+/// };
+/// ```
+pub fn get_plugin_config<T: DeserializeOwned>(
+    context: *mut bindings::OrthancPluginContext,
+    name: &str,
+) -> Result<T, ConfigError> {
+    get_configuration(context)
+        .ok_or(ConfigError::Unavailable)?
+        .section(name)
+}
+
 /// Translation of [OrthancPluginGetConfiguration](https://orthanc.uclouvain.be/sdk/OrthancCPlugin_8h_source.html#l03961).
 fn get_configuration_raw(
     context: *mut bindings::OrthancPluginContext,
@@ -49,6 +92,121 @@ impl OrthancConfigurationBuffer {
         let bytes = c_str.to_bytes();
         serde_json::from_slice(bytes)
     }
+
+    /// Deserialize a section of the Orthanc JSON config. See [get_plugin_config].
+    pub fn section<T: DeserializeOwned>(&self, name: &str) -> Result<T, ConfigError> {
+        let config: Map<String, Value> = self.deserialize().map_err(ConfigError::Json)?;
+        read_section(config, name, std::env::vars())
+    }
+}
+
+fn read_section<T: DeserializeOwned>(
+    mut config: Map<String, Value>,
+    name: &str,
+    env: impl Iterator<Item = (String, String)>,
+) -> Result<T, ConfigError> {
+    let mut section = config
+        .remove(name)
+        .unwrap_or_else(|| Value::Object(Map::new()));
+    let prefix = format!("ORTHANC__{}__", to_upper_snake_case(name));
+    // overrides which were parsed as JSON, which are used as strings instead
+    // if the field they set cannot be deserialized from JSON
+    let mut parsed_overrides = Vec::new();
+    for (key, value) in env {
+        if let Some(path) = key.strip_prefix(&prefix) {
+            let keys: Vec<_> = path.split("__").map(to_pascal_case).collect();
+            let parsed = match serde_json::from_str(&value) {
+                Ok(Value::String(_)) | Err(_) => Value::String(value),
+                Ok(parsed) => {
+                    parsed_overrides.push((keys.clone(), value));
+                    parsed
+                }
+            };
+            set_path(&mut section, keys.into_iter(), parsed);
+        }
+    }
+    loop {
+        let e = match serde_path_to_error::deserialize(section.clone()) {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+        let path = e.path().to_string();
+        if let Some(i) = parsed_overrides
+            .iter()
+            .position(|(keys, _)| keys.join(".") == path)
+        {
+            let (keys, value) = parsed_overrides.swap_remove(i);
+            set_path(&mut section, keys.into_iter(), Value::String(value));
+            continue;
+        }
+        let path = if path == "." {
+            name.to_string()
+        } else {
+            format!("{name}.{path}")
+        };
+        return Err(ConfigError::Invalid {
+            path,
+            error: e.into_inner(),
+        });
+    }
+}
+
+/// Set the value at `path`, replacing non-object values along the way with objects.
+fn set_path(mut target: &mut Value, path: impl Iterator<Item = String>, value: Value) {
+    for key in path {
+        if !target.is_object() {
+            *target = Value::Object(Map::new());
+        }
+        target = target
+            .as_object_mut()
+            .unwrap()
+            .entry(key)
+            .or_insert(Value::Null);
+    }
+    *target = value;
+}
+
+/// Convert `"LogLevel"` to `"LOG_LEVEL"`.
+fn to_upper_snake_case(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 4);
+    for (i, c) in s.chars().enumerate() {
+        if i > 0 && c.is_uppercase() {
+            result.push('_');
+        }
+        result.push(c.to_ascii_uppercase());
+    }
+    result
+}
+
+/// Convert `"LOG_LEVEL"` to `"LogLevel"`.
+fn to_pascal_case(s: &str) -> String {
+    s.split('_')
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars.map(|c| c.to_ascii_lowercase()))
+        })
+        .collect()
+}
+
+/// Error reading the configuration of a plugin.
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    /// Orthanc did not provide its configuration.
+    #[error("cannot get the Orthanc configuration")]
+    Unavailable,
+    /// The Orthanc configuration is not a JSON object.
+    #[error("invalid Orthanc configuration: {0}")]
+    Json(serde_json::Error),
+    /// A value in the plugin's section is invalid.
+    #[error("{path}: {error}")]
+    Invalid {
+        path: String,
+        error: serde_json::Error,
+    },
 }
 
 impl Drop for OrthancConfigurationBuffer {
@@ -56,3 +214,103 @@ impl Drop for OrthancConfigurationBuffer {
         unsafe { free_string(self.context, self.buffer) }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "PascalCase")]
+    struct ExampleConfig {
+        peer: String,
+        #[serde(default)]
+        log_level: Option<String>,
+        #[serde(default)]
+        retries: u32,
+    }
+
+    fn config(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_read_section_with_env_override() {
+        let env = [
+            ("ORTHANC__EXAMPLE__RETRIES".to_string(), "3".to_string()),
+            (
+                "ORTHANC__EXAMPLE__LOG_LEVEL".to_string(),
+                "debug".to_string(),
+            ),
+            ("ORTHANC__OTHER__PEER".to_string(), "ignored".to_string()),
+        ];
+        let actual: ExampleConfig = read_section(
+            config(json!({"Example": {"Peer": "pacs", "Retries": 1}})),
+            "Example",
+            env.into_iter(),
+        )
+        .unwrap();
+        let expected = ExampleConfig {
+            peer: "pacs".to_string(),
+            log_level: Some("debug".to_string()),
+            retries: 3,
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_read_section_env_override_of_string_field() {
+        let env = [
+            ("ORTHANC__EXAMPLE__PEER".to_string(), "12345".to_string()),
+            (
+                "ORTHANC__EXAMPLE__LOG_LEVEL".to_string(),
+                "true".to_string(),
+            ),
+        ];
+        let actual: ExampleConfig =
+            read_section(config(json!({})), "Example", env.into_iter()).unwrap();
+        let expected = ExampleConfig {
+            peer: "12345".to_string(),
+            log_level: Some("true".to_string()),
+            retries: 0,
+        };
+        assert_eq!(actual, expected);
+
+        let env = [("ORTHANC__EXAMPLE__RETRIES".to_string(), "many".to_string())];
+        let error = read_section::<ExampleConfig>(
+            config(json!({"Example": {"Peer": "pacs"}})),
+            "Example",
+            env.into_iter(),
+        )
+        .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("Example.Retries: invalid type")
+        );
+    }
+
+    #[test]
+    fn test_read_section_error_path() {
+        let error = read_section::<ExampleConfig>(
+            config(json!({"Example": {"Peer": 1}})),
+            "Example",
+            std::iter::empty(),
+        )
+        .unwrap_err();
+        assert!(error.to_string().starts_with("Example.Peer: invalid type"));
+        let error = read_section::<ExampleConfig>(config(json!({})), "Example", std::iter::empty())
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Example: missing field `Peer`");
+    }
+
+    #[test]
+    fn test_case_conversion() {
+        assert_eq!(
+            to_upper_snake_case("ExampleRustPlugin"),
+            "EXAMPLE_RUST_PLUGIN"
+        );
+        assert_eq!(to_pascal_case("MRN_FILE"), "MrnFile");
+    }
+}
@@ -1,48 +1,299 @@
-//! Traits for idiomatic error handling with [`OrthancPluginErrorCode`](OrthancPluginErrorCode).
+//! [OrthancErrorCode] for idiomatic error handling with [`OrthancPluginErrorCode`](OrthancPluginErrorCode).
 
+use crate::bindings;
 use crate::bindings::OrthancPluginErrorCode;
-use crate::bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_Success as Success;
-
-/// A [Result] wrapper for [`OrthancPluginErrorCode`](OrthancPluginErrorCode).
-///
-/// By convention, the [Ok] value _should_ be [`OrthancPluginErrorCode_Success`](Success)
-/// and the [Err] type should be all other values.
-///
-/// It can be unwrapped as an [`OrthancPluginErrorCode`](OrthancPluginErrorCode)
-/// using the [Into] trait.
-pub type ErrorCodeResult = Result<OrthancPluginErrorCode, OrthancPluginErrorCode>;
-
-/// Provides the [`into_result`](ErrorCode::into_result) method for idiomatic error handling.
-pub trait ErrorCode {
-    /// Produce [Ok] if the code is [`OrthancPluginErrorCode_Success`](Success),
-    /// or [Err] for any other value.
-    fn into_result(self) -> ErrorCodeResult;
-
-    /// If this is a success, return the given `code` instead.
-    fn map_ok(self, code: OrthancPluginErrorCode) -> OrthancPluginErrorCode;
+
+macro_rules! orthanc_error_codes {
+    ($($(#[$meta:meta])* $name:ident = $value:ident, $status:literal, $description:literal;)*) => {
+        /// An [`OrthancPluginErrorCode`](OrthancPluginErrorCode) as a Rust enum.
+        ///
+        /// The [Display](std::fmt::Display) messages are the same as those of
+        /// [OrthancPluginGetErrorDescription](https://orthanc.uclouvain.be/sdk/group__Toolbox.html).
+        /// Codes which are not known by this crate, e.g. error codes registered by plugins,
+        /// are [OrthancErrorCode::Other].
+        ///
+        /// Ref: <https://orthanc.uclouvain.be/hg/orthanc/file/tip/OrthancServer/Plugins/Include/orthanc/OrthancCPlugin.h>
+        #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+        #[non_exhaustive]
+        pub enum OrthancErrorCode {
+            $($(#[$meta])* $name,)*
+            /// An error code which is not defined by the Orthanc plugin SDK.
+            Other(OrthancPluginErrorCode),
+        }
+
+        impl OrthancErrorCode {
+            /// Names of the error codes defined by the Orthanc plugin SDK.
+            #[cfg(test)]
+            const NAMES: &[&str] = &[$(stringify!($name)),*];
+
+            /// Get the description of this error code.
+            pub fn description(&self) -> &'static str {
+                match self {
+                    $(Self::$name => $description,)*
+                    Self::Other(_) => "Error defined by a plugin or unknown error",
+                }
+            }
+
+            /// Get the HTTP status which Orthanc uses to report this error code.
+            pub fn http_status(&self) -> http::StatusCode {
+                let status = match self {
+                    $(Self::$name => $status,)*
+                    Self::Other(_) => 500,
+                };
+                http::StatusCode::from_u16(status).unwrap()
+            }
+        }
+
+        impl From<OrthancPluginErrorCode> for OrthancErrorCode {
+            fn from(value: OrthancPluginErrorCode) -> Self {
+                match value {
+                    $(bindings::$value => Self::$name,)*
+                    other => Self::Other(other),
+                }
+            }
+        }
+
+        impl From<OrthancErrorCode> for OrthancPluginErrorCode {
+            fn from(value: OrthancErrorCode) -> Self {
+                match value {
+                    $(OrthancErrorCode::$name => bindings::$value,)*
+                    OrthancErrorCode::Other(code) => code,
+                }
+            }
+        }
+    };
 }
 
-impl ErrorCode for OrthancPluginErrorCode {
-    fn into_result(self) -> ErrorCodeResult {
-        if self == Success { Ok(self) } else { Err(self) }
+orthanc_error_codes! {
+    InternalError = OrthancPluginErrorCode_OrthancPluginErrorCode_InternalError, 500, "Internal error";
+    Success = OrthancPluginErrorCode_OrthancPluginErrorCode_Success, 200, "Success";
+    Plugin = OrthancPluginErrorCode_OrthancPluginErrorCode_Plugin, 500, "Error encountered within the plugin engine";
+    NotImplemented = OrthancPluginErrorCode_OrthancPluginErrorCode_NotImplemented, 501, "Not implemented yet";
+    ParameterOutOfRange = OrthancPluginErrorCode_OrthancPluginErrorCode_ParameterOutOfRange, 400, "Parameter out of range";
+    NotEnoughMemory = OrthancPluginErrorCode_OrthancPluginErrorCode_NotEnoughMemory, 500, "The server hosting Orthanc is running out of memory";
+    BadParameterType = OrthancPluginErrorCode_OrthancPluginErrorCode_BadParameterType, 400, "Bad type for a parameter";
+    BadSequenceOfCalls = OrthancPluginErrorCode_OrthancPluginErrorCode_BadSequenceOfCalls, 500, "Bad sequence of calls";
+    InexistentItem = OrthancPluginErrorCode_OrthancPluginErrorCode_InexistentItem, 404, "Accessing an inexistent item";
+    BadRequest = OrthancPluginErrorCode_OrthancPluginErrorCode_BadRequest, 400, "Bad request";
+    NetworkProtocol = OrthancPluginErrorCode_OrthancPluginErrorCode_NetworkProtocol, 500, "Error in the network protocol";
+    SystemCommand = OrthancPluginErrorCode_OrthancPluginErrorCode_SystemCommand, 500, "Error while calling a system command";
+    Database = OrthancPluginErrorCode_OrthancPluginErrorCode_Database, 500, "Error with the database engine";
+    UriSyntax = OrthancPluginErrorCode_OrthancPluginErrorCode_UriSyntax, 400, "Badly formatted URI";
+    InexistentFile = OrthancPluginErrorCode_OrthancPluginErrorCode_InexistentFile, 404, "Inexistent file";
+    CannotWriteFile = OrthancPluginErrorCode_OrthancPluginErrorCode_CannotWriteFile, 500, "Cannot write to file";
+    BadFileFormat = OrthancPluginErrorCode_OrthancPluginErrorCode_BadFileFormat, 400, "Bad file format";
+    Timeout = OrthancPluginErrorCode_OrthancPluginErrorCode_Timeout, 500, "Timeout";
+    UnknownResource = OrthancPluginErrorCode_OrthancPluginErrorCode_UnknownResource, 404, "Unknown resource";
+    IncompatibleDatabaseVersion = OrthancPluginErrorCode_OrthancPluginErrorCode_IncompatibleDatabaseVersion, 500, "Incompatible version of the database";
+    FullStorage = OrthancPluginErrorCode_OrthancPluginErrorCode_FullStorage, 500, "The file storage is full";
+    CorruptedFile = OrthancPluginErrorCode_OrthancPluginErrorCode_CorruptedFile, 500, "Corrupted file (e.g. inconsistent MD5 hash)";
+    InexistentTag = OrthancPluginErrorCode_OrthancPluginErrorCode_InexistentTag, 404, "Inexistent tag";
+    ReadOnly = OrthancPluginErrorCode_OrthancPluginErrorCode_ReadOnly, 500, "Cannot modify a read-only data structure";
+    IncompatibleImageFormat = OrthancPluginErrorCode_OrthancPluginErrorCode_IncompatibleImageFormat, 500, "Incompatible format of the images";
+    IncompatibleImageSize = OrthancPluginErrorCode_OrthancPluginErrorCode_IncompatibleImageSize, 500, "Incompatible size of the images";
+    SharedLibrary = OrthancPluginErrorCode_OrthancPluginErrorCode_SharedLibrary, 500, "Error while using a shared library (plugin)";
+    UnknownPluginService = OrthancPluginErrorCode_OrthancPluginErrorCode_UnknownPluginService, 500, "Plugin invoking an unknown service";
+    UnknownDicomTag = OrthancPluginErrorCode_OrthancPluginErrorCode_UnknownDicomTag, 500, "Unknown DICOM tag";
+    BadJson = OrthancPluginErrorCode_OrthancPluginErrorCode_BadJson, 400, "Cannot parse a JSON document";
+    Unauthorized = OrthancPluginErrorCode_OrthancPluginErrorCode_Unauthorized, 401, "Bad credentials were provided to an HTTP request";
+    BadFont = OrthancPluginErrorCode_OrthancPluginErrorCode_BadFont, 400, "Badly formatted font file";
+    DatabasePlugin = OrthancPluginErrorCode_OrthancPluginErrorCode_DatabasePlugin, 500, "The plugin implementing a custom database back-end does not fulfill the proper interface";
+    StorageAreaPlugin = OrthancPluginErrorCode_OrthancPluginErrorCode_StorageAreaPlugin, 500, "Error in the plugin implementing a custom storage area";
+    EmptyRequest = OrthancPluginErrorCode_OrthancPluginErrorCode_EmptyRequest, 500, "The request is empty";
+    NotAcceptable = OrthancPluginErrorCode_OrthancPluginErrorCode_NotAcceptable, 406, "Cannot send a response which is acceptable according to the Accept HTTP header";
+    NullPointer = OrthancPluginErrorCode_OrthancPluginErrorCode_NullPointer, 500, "Cannot handle a NULL pointer";
+    DatabaseUnavailable = OrthancPluginErrorCode_OrthancPluginErrorCode_DatabaseUnavailable, 503, "The database is currently not available (probably a transient situation)";
+    CanceledJob = OrthancPluginErrorCode_OrthancPluginErrorCode_CanceledJob, 500, "This job was canceled";
+    BadGeometry = OrthancPluginErrorCode_OrthancPluginErrorCode_BadGeometry, 500, "Geometry error encountered in Stone";
+    SslInitialization = OrthancPluginErrorCode_OrthancPluginErrorCode_SslInitialization, 500, "Cannot initialize SSL encryption, check out your certificates";
+    DiscontinuedAbi = OrthancPluginErrorCode_OrthancPluginErrorCode_DiscontinuedAbi, 500, "Calling a function that has been removed from the Orthanc Framework";
+    BadRange = OrthancPluginErrorCode_OrthancPluginErrorCode_BadRange, 416, "Incorrect range request";
+    DatabaseCannotSerialize = OrthancPluginErrorCode_OrthancPluginErrorCode_DatabaseCannotSerialize, 503, "Database could not serialize access due to concurrent update, the transaction should be retried";
+    Revision = OrthancPluginErrorCode_OrthancPluginErrorCode_Revision, 409, "A bad revision number was provided, which might indicate conflict between multiple writers";
+    MainDicomTagsMultiplyDefined = OrthancPluginErrorCode_OrthancPluginErrorCode_MainDicomTagsMultiplyDefined, 500, "A main DICOM Tag has been defined multiple times for the same resource level";
+    ForbiddenAccess = OrthancPluginErrorCode_OrthancPluginErrorCode_ForbiddenAccess, 403, "Access to a resource is forbidden";
+    DuplicateResource = OrthancPluginErrorCode_OrthancPluginErrorCode_DuplicateResource, 409, "Duplicate resource";
+    IncompatibleConfigurations = OrthancPluginErrorCode_OrthancPluginErrorCode_IncompatibleConfigurations, 500, "Your configuration file contains configuration that are mutually incompatible";
+    SQLiteNotOpened = OrthancPluginErrorCode_OrthancPluginErrorCode_SQLiteNotOpened, 500, "SQLite: The database is not opened";
+    SQLiteAlreadyOpened = OrthancPluginErrorCode_OrthancPluginErrorCode_SQLiteAlreadyOpened, 500, "SQLite: Connection is already open";
+    SQLiteCannotOpen = OrthancPluginErrorCode_OrthancPluginErrorCode_SQLiteCannotOpen, 500, "SQLite: Unable to open the database";
+    SQLiteStatementAlreadyUsed = OrthancPluginErrorCode_OrthancPluginErrorCode_SQLiteStatementAlreadyUsed, 500, "SQLite: This cached statement is already being referred to";
+    SQLiteExecute = OrthancPluginErrorCode_OrthancPluginErrorCode_SQLiteExecute, 500, "SQLite: Cannot execute a command";
+    SQLiteRollbackWithoutTransaction = OrthancPluginErrorCode_OrthancPluginErrorCode_SQLiteRollbackWithoutTransaction, 500, "SQLite: Rolling back a nonexistent transaction (have you called Begin()?)";
+    SQLiteCommitWithoutTransaction = OrthancPluginErrorCode_OrthancPluginErrorCode_SQLiteCommitWithoutTransaction, 500, "SQLite: Committing a nonexistent transaction";
+    SQLiteRegisterFunction = OrthancPluginErrorCode_OrthancPluginErrorCode_SQLiteRegisterFunction, 500, "SQLite: Unable to register a function";
+    SQLiteFlush = OrthancPluginErrorCode_OrthancPluginErrorCode_SQLiteFlush, 500, "SQLite: Unable to flush the database";
+    SQLiteCannotRun = OrthancPluginErrorCode_OrthancPluginErrorCode_SQLiteCannotRun, 500, "SQLite: Cannot run a cached statement";
+    SQLiteCannotStep = OrthancPluginErrorCode_OrthancPluginErrorCode_SQLiteCannotStep, 500, "SQLite: Cannot step over a cached statement";
+    SQLiteBindOutOfRange = OrthancPluginErrorCode_OrthancPluginErrorCode_SQLiteBindOutOfRange, 500, "SQLite: Bing a value while out of range (serious error)";
+    SQLitePrepareStatement = OrthancPluginErrorCode_OrthancPluginErrorCode_SQLitePrepareStatement, 500, "SQLite: Cannot prepare a cached statement";
+    SQLiteTransactionAlreadyStarted = OrthancPluginErrorCode_OrthancPluginErrorCode_SQLiteTransactionAlreadyStarted, 500, "SQLite: Beginning the same transaction twice";
+    SQLiteTransactionCommit = OrthancPluginErrorCode_OrthancPluginErrorCode_SQLiteTransactionCommit, 500, "SQLite: Failure when committing the transaction";
+    SQLiteTransactionBegin = OrthancPluginErrorCode_OrthancPluginErrorCode_SQLiteTransactionBegin, 500, "SQLite: Cannot start a transaction";
+    DirectoryOverFile = OrthancPluginErrorCode_OrthancPluginErrorCode_DirectoryOverFile, 500, "The directory to be created is already occupied by a regular file";
+    FileStorageCannotWrite = OrthancPluginErrorCode_OrthancPluginErrorCode_FileStorageCannotWrite, 500, "Unable to create a subdirectory or a file in the file storage";
+    DirectoryExpected = OrthancPluginErrorCode_OrthancPluginErrorCode_DirectoryExpected, 500, "The specified path does not point to a directory";
+    HttpPortInUse = OrthancPluginErrorCode_OrthancPluginErrorCode_HttpPortInUse, 500, "The TCP port of the HTTP server is privileged or already in use";
+    DicomPortInUse = OrthancPluginErrorCode_OrthancPluginErrorCode_DicomPortInUse, 500, "The TCP port of the DICOM server is privileged or already in use";
+    BadHttpStatusInRest = OrthancPluginErrorCode_OrthancPluginErrorCode_BadHttpStatusInRest, 500, "This HTTP status is not allowed in a REST API";
+    RegularFileExpected = OrthancPluginErrorCode_OrthancPluginErrorCode_RegularFileExpected, 500, "The specified path does not point to a regular file";
+    PathToExecutable = OrthancPluginErrorCode_OrthancPluginErrorCode_PathToExecutable, 500, "Unable to get the path to the executable";
+    MakeDirectory = OrthancPluginErrorCode_OrthancPluginErrorCode_MakeDirectory, 500, "Cannot create a directory";
+    BadApplicationEntityTitle = OrthancPluginErrorCode_OrthancPluginErrorCode_BadApplicationEntityTitle, 500, "An application entity title (AET) cannot be empty or be longer than 16 characters";
+    NoCFindHandler = OrthancPluginErrorCode_OrthancPluginErrorCode_NoCFindHandler, 500, "No request handler factory for DICOM C-FIND SCP";
+    NoCMoveHandler = OrthancPluginErrorCode_OrthancPluginErrorCode_NoCMoveHandler, 500, "No request handler factory for DICOM C-MOVE SCP";
+    NoCStoreHandler = OrthancPluginErrorCode_OrthancPluginErrorCode_NoCStoreHandler, 500, "No request handler factory for DICOM C-STORE SCP";
+    NoApplicationEntityFilter = OrthancPluginErrorCode_OrthancPluginErrorCode_NoApplicationEntityFilter, 500, "No application entity filter";
+    NoSopClassOrInstance = OrthancPluginErrorCode_OrthancPluginErrorCode_NoSopClassOrInstance, 500, "DicomUserConnection: Unable to find the SOP class and instance";
+    NoPresentationContext = OrthancPluginErrorCode_OrthancPluginErrorCode_NoPresentationContext, 500, "DicomUserConnection: No acceptable presentation context for modality";
+    DicomFindUnavailable = OrthancPluginErrorCode_OrthancPluginErrorCode_DicomFindUnavailable, 500, "DicomUserConnection: The C-FIND command is not supported by the remote SCP";
+    DicomMoveUnavailable = OrthancPluginErrorCode_OrthancPluginErrorCode_DicomMoveUnavailable, 500, "DicomUserConnection: The C-MOVE command is not supported by the remote SCP";
+    CannotStoreInstance = OrthancPluginErrorCode_OrthancPluginErrorCode_CannotStoreInstance, 500, "Cannot store an instance";
+    CreateDicomNotString = OrthancPluginErrorCode_OrthancPluginErrorCode_CreateDicomNotString, 400, "Only string values are supported when creating DICOM instances";
+    CreateDicomOverrideTag = OrthancPluginErrorCode_OrthancPluginErrorCode_CreateDicomOverrideTag, 400, "Trying to override a value inherited from a parent module";
+    CreateDicomUseContent = OrthancPluginErrorCode_OrthancPluginErrorCode_CreateDicomUseContent, 400, "Use \"Content\" to inject an image into a new DICOM instance";
+    CreateDicomNoPayload = OrthancPluginErrorCode_OrthancPluginErrorCode_CreateDicomNoPayload, 400, "No payload is present for one instance in the series";
+    CreateDicomUseDataUriScheme = OrthancPluginErrorCode_OrthancPluginErrorCode_CreateDicomUseDataUriScheme, 400, "The payload of the DICOM instance must be specified according to Data URI scheme";
+    CreateDicomBadParent = OrthancPluginErrorCode_OrthancPluginErrorCode_CreateDicomBadParent, 400, "Trying to attach a new DICOM instance to an inexistent resource";
+    CreateDicomParentIsInstance = OrthancPluginErrorCode_OrthancPluginErrorCode_CreateDicomParentIsInstance, 400, "Trying to attach a new DICOM instance to an instance (must be a series, study or patient)";
+    CreateDicomParentEncoding = OrthancPluginErrorCode_OrthancPluginErrorCode_CreateDicomParentEncoding, 400, "Unable to get the encoding of the parent resource";
+    UnknownModality = OrthancPluginErrorCode_OrthancPluginErrorCode_UnknownModality, 404, "Unknown modality";
+    BadJobOrdering = OrthancPluginErrorCode_OrthancPluginErrorCode_BadJobOrdering, 500, "Bad ordering of filters in a job";
+    JsonToLuaTable = OrthancPluginErrorCode_OrthancPluginErrorCode_JsonToLuaTable, 500, "Cannot convert the given JSON object to a Lua table";
+    CannotCreateLua = OrthancPluginErrorCode_OrthancPluginErrorCode_CannotCreateLua, 500, "Cannot create the Lua context";
+    CannotExecuteLua = OrthancPluginErrorCode_OrthancPluginErrorCode_CannotExecuteLua, 500, "Cannot execute a Lua command";
+    LuaAlreadyExecuted = OrthancPluginErrorCode_OrthancPluginErrorCode_LuaAlreadyExecuted, 500, "Arguments cannot be pushed after the Lua function is executed";
+    LuaBadOutput = OrthancPluginErrorCode_OrthancPluginErrorCode_LuaBadOutput, 500, "The Lua function does not give the expected number of outputs";
+    NotLuaPredicate = OrthancPluginErrorCode_OrthancPluginErrorCode_NotLuaPredicate, 500, "The Lua function is not a predicate (only true/false outputs allowed)";
+    LuaReturnsNoString = OrthancPluginErrorCode_OrthancPluginErrorCode_LuaReturnsNoString, 500, "The Lua function does not return a string";
+    StorageAreaAlreadyRegistered = OrthancPluginErrorCode_OrthancPluginErrorCode_StorageAreaAlreadyRegistered, 500, "Another plugin has already registered a custom storage area";
+    DatabaseBackendAlreadyRegistered = OrthancPluginErrorCode_OrthancPluginErrorCode_DatabaseBackendAlreadyRegistered, 500, "Another plugin has already registered a custom database back-end";
+    DatabaseNotInitialized = OrthancPluginErrorCode_OrthancPluginErrorCode_DatabaseNotInitialized, 500, "Plugin trying to call the database during its initialization";
+    SslDisabled = OrthancPluginErrorCode_OrthancPluginErrorCode_SslDisabled, 500, "Orthanc has been built without SSL support";
+    CannotOrderSlices = OrthancPluginErrorCode_OrthancPluginErrorCode_CannotOrderSlices, 500, "Unable to order the slices of the series";
+    NoWorklistHandler = OrthancPluginErrorCode_OrthancPluginErrorCode_NoWorklistHandler, 500, "No request handler factory for DICOM C-Find Modality SCP";
+    AlreadyExistingTag = OrthancPluginErrorCode_OrthancPluginErrorCode_AlreadyExistingTag, 500, "Cannot override the value of a tag that already exists";
+    NoStorageCommitmentHandler = OrthancPluginErrorCode_OrthancPluginErrorCode_NoStorageCommitmentHandler, 500, "No request handler factory for DICOM N-ACTION SCP (storage commitment)";
+    NoCGetHandler = OrthancPluginErrorCode_OrthancPluginErrorCode_NoCGetHandler, 500, "No request handler factory for DICOM C-GET SCP";
+    DicomGetUnavailable = OrthancPluginErrorCode_OrthancPluginErrorCode_DicomGetUnavailable, 500, "DicomUserConnection: The C-GET command is not supported by the remote SCP";
+    UnsupportedMediaType = OrthancPluginErrorCode_OrthancPluginErrorCode_UnsupportedMediaType, 415, "Unsupported media type";
+}
+
+impl OrthancErrorCode {
+    /// Returns `true` if this is [OrthancErrorCode::Success].
+    pub fn is_success(&self) -> bool {
+        *self == Self::Success
     }
 
-    fn map_ok(self, code: OrthancPluginErrorCode) -> OrthancPluginErrorCode {
-        if self == Success { code } else { self }
+    /// Produce [Ok] if the code is [OrthancErrorCode::Success], or [Err] for any other value.
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_success() { Ok(()) } else { Err(self) }
     }
 }
 
-/// Provides the [`into_code`](IntoOrthancPluginErrorCode::into_code) method.
-pub trait IntoOrthancPluginErrorCode {
-    /// Convert to [`OrthancPluginErrorCode`](OrthancPluginErrorCode).
-    fn into_code(self) -> OrthancPluginErrorCode;
+impl std::fmt::Display for OrthancErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let code = OrthancPluginErrorCode::from(*self);
+        write!(f, "{} (code {code})", self.description())
+    }
 }
 
-impl IntoOrthancPluginErrorCode for ErrorCodeResult {
-    fn into_code(self) -> OrthancPluginErrorCode {
-        match self {
-            Ok(code) => code,
-            Err(code) => code,
+impl std::error::Error for OrthancErrorCode {}
+
+impl From<http::StatusCode> for OrthancErrorCode {
+    /// Get the error code which best describes an HTTP status.
+    fn from(status: http::StatusCode) -> Self {
+        match status.as_u16() {
+            200..=299 => Self::Success,
+            401 => Self::Unauthorized,
+            403 => Self::ForbiddenAccess,
+            404 => Self::UnknownResource,
+            406 => Self::NotAcceptable,
+            409 => Self::DuplicateResource,
+            415 => Self::UnsupportedMediaType,
+            416 => Self::BadRange,
+            501 => Self::NotImplemented,
+            503 => Self::DatabaseUnavailable,
+            400..=499 => Self::BadRequest,
+            _ => Self::InternalError,
         }
     }
 }
+
+impl From<OrthancErrorCode> for http::StatusCode {
+    fn from(value: OrthancErrorCode) -> Self {
+        value.http_status()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion() {
+        assert_eq!(
+            OrthancErrorCode::from(
+                bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_InternalError
+            ),
+            OrthancErrorCode::InternalError
+        );
+        assert_eq!(
+            OrthancErrorCode::from(
+                bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_UnsupportedMediaType
+            ),
+            OrthancErrorCode::UnsupportedMediaType
+        );
+        assert_eq!(
+            OrthancPluginErrorCode::from(OrthancErrorCode::InexistentFile),
+            bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_InexistentFile
+        );
+        assert_eq!(
+            OrthancErrorCode::from(1000000),
+            OrthancErrorCode::Other(1000000)
+        );
+        assert_eq!(
+            OrthancPluginErrorCode::from(OrthancErrorCode::Other(1000000)),
+            1000000
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            OrthancErrorCode::InexistentFile.to_string(),
+            "Inexistent file (code 13)"
+        );
+    }
+
+    #[test]
+    fn test_http_status() {
+        assert_eq!(
+            OrthancErrorCode::UnknownResource.http_status(),
+            http::StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            OrthancErrorCode::from(http::StatusCode::NOT_FOUND),
+            OrthancErrorCode::UnknownResource
+        );
+        assert_eq!(
+            OrthancErrorCode::from(http::StatusCode::IM_A_TEAPOT),
+            OrthancErrorCode::BadRequest
+        );
+    }
+
+    /// Every error code of the bindings must be a variant of [OrthancErrorCode].
+    #[test]
+    fn test_all_codes_of_bindings() {
+        let prefix = "pub const OrthancPluginErrorCode_OrthancPluginErrorCode_";
+        let mut codes: Vec<_> = include_str!("bindings.rs")
+            .split(prefix)
+            .skip(1)
+            .map(|rest| rest.split(':').next().unwrap().trim())
+            .collect();
+        codes.sort();
+        let mut names = OrthancErrorCode::NAMES.to_vec();
+        names.sort();
+        assert_eq!(names, codes);
+    }
+}
@@ -3,6 +3,7 @@
 // TODO HTTP headers are not handled
 
 use super::bindings;
+use crate::error_code::OrthancErrorCode;
 use serde::Serialize;
 use std::ffi::CStr;
 
@@ -51,6 +52,60 @@ impl<S: Serialize> From<http::StatusCode> for Response<S> {
     }
 }
 
+/// An error which Orthanc reports using its standard error JSON.
+///
+/// The HTTP status is that of [RestError::code], e.g. a code registered with
+/// [crate::register_error_code]. [RestError::details] is reported in the
+/// `"Details"` field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestError {
+    pub code: OrthancErrorCode,
+    pub details: Option<String>,
+    /// Also write the details to the Orthanc log.
+    pub log: bool,
+}
+
+impl RestError {
+    /// Create an error without details.
+    pub fn new(code: OrthancErrorCode) -> Self {
+        Self {
+            code,
+            details: None,
+            log: false,
+        }
+    }
+
+    /// Set the details of this error.
+    pub fn with_details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+}
+
+impl From<OrthancErrorCode> for RestError {
+    fn from(code: OrthancErrorCode) -> Self {
+        Self::new(code)
+    }
+}
+
+/// A value which can be returned by the handler given to [crate::create_json_rest_callback]:
+/// either a [Response] or a [Result] of a [Response] and a [RestError].
+pub trait IntoRestAnswer<S: Serialize> {
+    fn into_answer(self) -> Result<Response<S>, RestError>;
+}
+
+impl<S: Serialize, R: Into<Response<S>>> IntoRestAnswer<S> for R {
+    fn into_answer(self) -> Result<Response<S>, RestError> {
+        Ok(self.into())
+    }
+}
+
+impl<S: Serialize, R: Into<Response<S>>> IntoRestAnswer<S> for Result<R, RestError> {
+    fn into_answer(self) -> Result<Response<S>, RestError> {
+        self.map(Into::into)
+    }
+}
+
 /// A HTTP request to Orthanc.
 pub struct Request<'a, D: serde::Deserialize<'a>> {
     pub url: &'a str,
@@ -67,7 +122,7 @@ impl<'a, D: serde::Deserialize<'a>> Request<'a, D> {
         let method = match Method::try_from(unsafe { (*request).method }) {
             Ok(method) => method,
             Err(()) => {
-                return Err(bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_BadRequest);
+                return Err(OrthancErrorCode::BadRequest.into());
             }
         };
 
@@ -75,7 +130,7 @@ impl<'a, D: serde::Deserialize<'a>> Request<'a, D> {
         let url = match c_url.to_str() {
             Ok(s) => s,
             Err(_) => {
-                return Err(bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_BadRequest);
+                return Err(OrthancErrorCode::BadRequest.into());
             }
         };
 
@@ -91,7 +146,7 @@ impl<'a, D: serde::Deserialize<'a>> Request<'a, D> {
             match serde_json::from_slice(slice) {
                 Ok(body) => body,
                 Err(_e) => {
-                    return Err(bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_BadJson);
+                    return Err(OrthancErrorCode::BadJson.into());
                 }
             }
         };
@@ -144,6 +199,17 @@ impl TryFrom<bindings::OrthancPluginHttpMethod> for Method {
     }
 }
 
+impl From<Method> for bindings::OrthancPluginHttpMethod {
+    fn from(value: Method) -> Self {
+        match value {
+            Method::Get => bindings::OrthancPluginHttpMethod_OrthancPluginHttpMethod_Get,
+            Method::Post => bindings::OrthancPluginHttpMethod_OrthancPluginHttpMethod_Post,
+            Method::Put => bindings::OrthancPluginHttpMethod_OrthancPluginHttpMethod_Put,
+            Method::Delete => bindings::OrthancPluginHttpMethod_OrthancPluginHttpMethod_Delete,
+        }
+    }
+}
+
 impl<A: Serialize> From<Result<Response<A>, Response<A>>> for Response<A> {
     fn from(value: Result<Response<A>, Response<A>>) -> Self {
         value.unwrap_or_else(|value| value)
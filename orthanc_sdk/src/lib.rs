@@ -22,6 +22,7 @@
 )]
 pub mod bindings;
 mod error_code;
+pub use error_code::OrthancErrorCode;
 pub use orthanc_client_ogen::models as openapi;
 
 pub mod api;
@@ -29,14 +30,16 @@ pub mod http;
 pub mod utils;
 
 mod config;
+mod logger;
+mod panic;
 mod rest;
 mod sdk;
-mod tracing_subscriber;
 
-pub use config::{OrthancConfigurationBuffer, get_configuration};
+pub use config::{ConfigError, OrthancConfigurationBuffer, get_configuration, get_plugin_config};
+pub use logger::{LogCategory, OrthancLogger, log_filter};
+pub use panic::catch_panic;
 pub use rest::*;
 pub use sdk::*;
-pub use tracing_subscriber::OrthancLogger;
 
 #[cfg(feature = "webapp")]
 pub mod webapp;
@@ -0,0 +1,296 @@
+use std::ffi::CString;
+
+use super::sdk::invoke_service;
+use crate::bindings;
+use tracing::{Level, Subscriber, span};
+use tracing_subscriber::Layer;
+use tracing_subscriber::filter::{EnvFilter, LevelFilter, ParseError};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+/// A [tracing_subscriber::Layer] which calls
+/// [OrthancPluginLogMessage](https://orthanc.uclouvain.be/sdk/group__Toolbox.html#ga0d9d440dc5622861d357920c12662c95).
+///
+/// Fields of the spans which an event is in are written before the fields of the event.
+/// Nul bytes in messages are escaped as `\0`.
+///
+/// ## Example
+///
+/// ```no_run
+/// use tracing_subscriber::prelude::*;
+///
+/// # let context = std::ptr::null_mut();
+/// let logger = orthanc_sdk::OrthancLogger::new(context, "my_plugin");
+/// let filter = orthanc_sdk::log_filter("my_plugin=debug,orthanc_sdk=warn").unwrap();
+/// let subscriber = tracing_subscriber::registry().with(filter).with(logger);
+/// tracing::subscriber::set_global_default(subscriber).unwrap();
+/// ```
+pub struct OrthancLogger {
+    /// Orthanc plugin context
+    pub context: *mut bindings::OrthancPluginContext,
+    /// Plugin name
+    pub plugin_name: &'static str,
+    /// Force [Level::INFO] to be interpreted as [bindings::OrthancPluginLogLevel_OrthancPluginLogLevel_Warning]
+    pub verbose: bool,
+    /// Log category of events by target prefix. See [OrthancLogger::category].
+    pub categories: Vec<(&'static str, LogCategory)>,
+}
+
+unsafe impl Send for OrthancLogger {}
+unsafe impl Sync for OrthancLogger {}
+
+impl OrthancLogger {
+    /// Create an [OrthancLogger] with the default target-to-category mapping
+    /// for the modules of this crate.
+    pub fn new(context: *mut bindings::OrthancPluginContext, plugin_name: &'static str) -> Self {
+        Self {
+            context,
+            plugin_name,
+            verbose: false,
+            categories: vec![
+                ("orthanc_sdk::http", LogCategory::Http),
+                ("orthanc_sdk::rest", LogCategory::Http),
+                ("orthanc_sdk::webapp", LogCategory::Http),
+                ("orthanc_sdk::api::dicom", LogCategory::Dicom),
+                ("orthanc_sdk::api::modalities", LogCategory::Dicom),
+                ("orthanc_sdk::utils::jobs", LogCategory::Jobs),
+            ],
+        }
+    }
+
+    /// Log events with targets in the module `target` (or its submodules)
+    /// as `category`.
+    pub fn with_category(mut self, target: &'static str, category: LogCategory) -> Self {
+        self.categories.push((target, category));
+        self
+    }
+
+    /// Get the log category of a target.
+    ///
+    /// The category of the longest matching target prefix is used.
+    /// Events of targets without a match are [LogCategory::Plugins].
+    pub fn category(&self, target: &str) -> LogCategory {
+        self.categories
+            .iter()
+            .filter(|(prefix, _)| is_module_of(target, prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, category)| *category)
+            .unwrap_or(LogCategory::Plugins)
+    }
+}
+
+/// Create a filter from per-target level directives, e.g. `"blt=debug,orthanc_sdk=warn"`.
+///
+/// The syntax is that of [EnvFilter]. Events of targets without a matching directive
+/// are enabled at [Level::INFO] and above. Disabled events are filtered out before
+/// their fields are formatted, so they cost (almost) nothing.
+///
+/// The directives would typically be read from the plugin's section of the Orthanc
+/// configuration file (see [crate::get_configuration]), e.g.
+///
+/// ```json
+/// {
+///   "MyPlugin": {
+///     "LogLevel": "my_plugin=debug,orthanc_sdk=warn"
+///   }
+/// }
+/// ```
+pub fn log_filter(directives: &str) -> Result<EnvFilter, ParseError> {
+    EnvFilter::builder()
+        .with_default_directive(LevelFilter::INFO.into())
+        .parse(directives)
+}
+
+/// Returns `true` if `target` is the module `prefix` or one of its submodules.
+fn is_module_of(target: &str, prefix: &str) -> bool {
+    target
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+/// Orthanc log category.
+///
+/// Ref: <https://orthanc.uclouvain.be/sdk/group__Toolbox.html>
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum LogCategory {
+    Generic,
+    Plugins,
+    Http,
+    Sqlite,
+    Dicom,
+    Jobs,
+    Lua,
+}
+
+impl From<LogCategory> for bindings::OrthancPluginLogCategory {
+    fn from(value: LogCategory) -> Self {
+        match value {
+            LogCategory::Generic => {
+                bindings::OrthancPluginLogCategory_OrthancPluginLogCategory_Generic
+            }
+            LogCategory::Plugins => {
+                bindings::OrthancPluginLogCategory_OrthancPluginLogCategory_Plugins
+            }
+            LogCategory::Http => bindings::OrthancPluginLogCategory_OrthancPluginLogCategory_Http,
+            LogCategory::Sqlite => {
+                bindings::OrthancPluginLogCategory_OrthancPluginLogCategory_Sqlite
+            }
+            LogCategory::Dicom => bindings::OrthancPluginLogCategory_OrthancPluginLogCategory_Dicom,
+            LogCategory::Jobs => bindings::OrthancPluginLogCategory_OrthancPluginLogCategory_Jobs,
+            LogCategory::Lua => bindings::OrthancPluginLogCategory_OrthancPluginLogCategory_Lua,
+        }
+    }
+}
+
+/// Formatted fields of a span, stored in its extensions.
+struct SpanFields(Vec<String>);
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for OrthancLogger {
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::new();
+        attrs.record(&mut visitor);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanFields(visitor.data));
+        }
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::new();
+        values.record(&mut visitor);
+        if let Some(span) = ctx.span(id)
+            && let Some(fields) = span.extensions_mut().get_mut::<SpanFields>()
+        {
+            fields.0.extend(visitor.data);
+        }
+    }
+
+    fn on_event(&self, event: &tracing::Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::new();
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                if let Some(fields) = span.extensions().get::<SpanFields>() {
+                    visitor.data.extend(fields.0.iter().cloned());
+                }
+            }
+        }
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        let c_message = to_cstring(visitor.into_message());
+        let c_plugin = to_cstring(self.plugin_name.to_string());
+        let c_file = to_cstring(metadata.file().unwrap_or("unknown").to_string());
+        let params = bindings::_OrthancPluginLogMessage {
+            message: c_message.as_ptr(),
+            plugin: c_plugin.as_ptr(),
+            file: c_file.as_ptr(),
+            line: metadata.line().unwrap_or(0),
+            category: self.category(metadata.target()).into(),
+            level: to_orthanc_level(metadata.level(), self.verbose),
+        };
+        let code = invoke_service(
+            self.context,
+            bindings::_OrthancPluginService__OrthancPluginService_LogMessage,
+            params,
+        );
+        if code != bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_Success {
+            eprintln!("ERROR: OrthancPluginLogMessage (code {code})");
+        }
+    }
+}
+
+#[allow(clippy::if_same_then_else)]
+fn to_orthanc_level(level: &Level, verbose: bool) -> bindings::OrthancPluginLogLevel {
+    if level <= &Level::ERROR {
+        bindings::OrthancPluginLogLevel_OrthancPluginLogLevel_Error
+    } else if level <= &Level::WARN {
+        bindings::OrthancPluginLogLevel_OrthancPluginLogLevel_Warning
+    } else if level >= &Level::INFO && verbose {
+        bindings::OrthancPluginLogLevel_OrthancPluginLogLevel_Warning
+    } else if level <= &Level::INFO {
+        bindings::OrthancPluginLogLevel_OrthancPluginLogLevel_Info
+    } else if level <= &Level::DEBUG {
+        bindings::OrthancPluginLogLevel_OrthancPluginLogLevel_Trace
+    } else if level <= &Level::TRACE {
+        bindings::OrthancPluginLogLevel_OrthancPluginLogLevel_Trace
+    } else {
+        bindings::OrthancPluginLogLevel_OrthancPluginLogLevel_Trace
+    }
+}
+
+/// Convert to [CString], escaping nul bytes.
+fn to_cstring(s: String) -> CString {
+    CString::new(s).unwrap_or_else(|e| {
+        let escaped = String::from_utf8_lossy(&e.into_vec()).replace('\0', "\\0");
+        CString::new(escaped).unwrap()
+    })
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    data: Vec<String>,
+    message: Option<String>,
+}
+
+impl FieldVisitor {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn into_message(self) -> String {
+        let data = self.data.join(" ");
+        if let Some(message) = self.message {
+            if data.is_empty() {
+                message
+            } else {
+                format!("{data} {message}")
+            }
+        } else {
+            data
+        }
+    }
+}
+
+impl tracing::field::Visit for FieldVisitor {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message.replace(format!("{value:?}"));
+        } else {
+            let s = format!("{}={value:?}", field.name());
+            self.data.push(s);
+        }
+    }
+
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        let s = format!("{}=\"{}\"", field.name(), value.replace('"', "\\\""));
+        self.data.push(s);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category() {
+        let logger = OrthancLogger::new(std::ptr::null_mut(), "test")
+            .with_category("blt", LogCategory::Jobs)
+            .with_category("blt::http", LogCategory::Http);
+        assert_eq!(logger.category("blt"), LogCategory::Jobs);
+        assert_eq!(logger.category("blt::push"), LogCategory::Jobs);
+        assert_eq!(logger.category("blt::http::routes"), LogCategory::Http);
+        assert_eq!(logger.category("blt_other"), LogCategory::Plugins);
+        assert_eq!(logger.category("orthanc_sdk::webapp"), LogCategory::Http);
+    }
+
+    #[test]
+    fn test_to_cstring_escapes_nul() {
+        let actual = to_cstring("a\0b".to_string());
+        assert_eq!(actual.to_str().unwrap(), "a\\0b");
+    }
+
+    #[test]
+    fn test_log_filter() {
+        assert!(log_filter("blt=debug,orthanc_sdk=warn").is_ok());
+        assert!(log_filter("").is_ok());
+        assert!(log_filter("blt=loud").is_err());
+    }
+}
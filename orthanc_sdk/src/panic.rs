@@ -0,0 +1,57 @@
+//! Panic safety for functions called by Orthanc through the C ABI.
+//!
+//! A panic must never unwind across an `extern "C"` boundary. Every callback
+//! invoked by Orthanc should call [catch_panic] so that a panic is logged and
+//! reported to Orthanc as an error code instead.
+
+use crate::bindings;
+use crate::error_code::OrthancErrorCode;
+use std::any::Any;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+/// Call `f`, catching any panic it produces.
+///
+/// A caught panic is reported with [tracing::error] and converted to
+/// [`OrthancPluginErrorCode_InternalError`](bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_InternalError).
+///
+/// ## Example
+///
+/// ```
+/// use orthanc_sdk::bindings;
+///
+/// extern "C" fn rest_callback(
+///     _output: *mut bindings::OrthancPluginRestOutput,
+///     _url: *const std::ffi::c_char,
+///     _request: *const bindings::OrthancPluginHttpRequest,
+/// ) -> bindings::OrthancPluginErrorCode {
+///     orthanc_sdk::catch_panic(|| {
+///         // ... handle the request ...
+///         bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_Success
+///     })
+/// }
+/// ```
+pub fn catch_panic<F: FnOnce() -> bindings::OrthancPluginErrorCode>(
+    f: F,
+) -> bindings::OrthancPluginErrorCode {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(code) => code,
+        Err(payload) => {
+            tracing::error!(
+                panic = panic_message(payload.as_ref()),
+                "caught panic in Rust plugin code"
+            );
+            OrthancErrorCode::InternalError.into()
+        }
+    }
+}
+
+/// Get the message of a panic payload, if it is a string.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(s) = payload.downcast_ref::<&'static str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.as_str()
+    } else {
+        "unknown panic payload"
+    }
+}
@@ -1,20 +1,44 @@
 //! REST related helper functions.
 
 use crate::bindings;
-use crate::error_code::*;
-use crate::http::{Request, Response};
-use crate::sdk::send_http_status_code;
+use crate::error_code::OrthancErrorCode;
+use crate::http::{IntoRestAnswer, Request};
+use crate::panic::catch_panic;
 use crate::sdk::{answer_buffer, send_http_status};
+use crate::sdk::{send_http_status_code, set_http_error_details};
 use http::StatusCode;
 use std::ffi::CStr;
 
 /// Create an Orthanc REST callback that uses JSON in its request and response bodies.
+///
+/// `handle` may return a [RestError](crate::http::RestError), which Orthanc reports
+/// using its standard error JSON.
+///
+/// If `handle` panics, the panic is caught and
+/// [`OrthancPluginErrorCode_InternalError`](bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_InternalError)
+/// is returned. See [catch_panic].
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub fn create_json_rest_callback<
     'a,
     S: serde::Serialize,
     D: serde::Deserialize<'a>,
-    R: Into<Response<S>>,
+    R: IntoRestAnswer<S>,
+    F: FnOnce(Request<D>) -> R,
+>(
+    context: *mut bindings::OrthancPluginContext,
+    output: *mut bindings::OrthancPluginRestOutput,
+    url: *const std::os::raw::c_char,
+    request: *const bindings::OrthancPluginHttpRequest,
+    handle: F,
+) -> bindings::OrthancPluginErrorCode {
+    catch_panic(|| json_rest_callback_impl(context, output, url, request, handle))
+}
+
+fn json_rest_callback_impl<
+    'a,
+    S: serde::Serialize,
+    D: serde::Deserialize<'a>,
+    R: IntoRestAnswer<S>,
     F: FnOnce(Request<D>) -> R,
 >(
     context: *mut bindings::OrthancPluginContext,
@@ -29,13 +53,23 @@ pub fn create_json_rest_callback<
             return e;
         }
     };
-    let res = handle(req).into();
+    let res = match handle(req).into_answer() {
+        Ok(res) => res,
+        Err(e) => {
+            if let Some(details) = &e.details
+                && let Err(code) = set_http_error_details(context, output, details, e.log)
+            {
+                tracing::warn!("cannot set HTTP error details: {code}");
+            }
+            return e.code.into();
+        }
+    };
 
     if let Some(body) = &res.body {
         let body = match serde_json::to_vec(body) {
             Ok(body) => body,
             Err(_e) => {
-                return bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_InternalError;
+                return OrthancErrorCode::InternalError.into();
             }
         };
         respond_with_body(context, output, res.code, body, c"application/json")
@@ -59,27 +93,43 @@ fn respond_with_body(
         StatusCode::OK => answer_buffer(context, output, &body, mime_type),
         StatusCode::MOVED_PERMANENTLY => {
             // TODO must use ::OrthancPluginRedirect()
-            bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_NotImplemented
+            OrthancErrorCode::NotImplemented.into()
         }
         StatusCode::UNAUTHORIZED => {
             // TODO must use ::OrthancPluginSendUnauthorized()
-            bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_NotImplemented
+            OrthancErrorCode::NotImplemented.into()
         }
         StatusCode::METHOD_NOT_ALLOWED => {
             // TODO must use ::OrthancPluginSendMethodNotAllowed()
-            bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_NotImplemented
+            OrthancErrorCode::NotImplemented.into()
         }
-        StatusCode::NOT_ACCEPTABLE => send_http_status(context, output, code.as_u16(), body)
-            .map_ok(bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_NotAcceptable),
-        StatusCode::NOT_IMPLEMENTED => send_http_status(context, output, code.as_u16(), body)
-            .map_ok(bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_NotImplemented),
-        StatusCode::BAD_REQUEST => send_http_status(context, output, code.as_u16(), body)
-            .map_ok(bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_BadRequest),
+        StatusCode::NOT_ACCEPTABLE => or_error(
+            send_http_status(context, output, code.as_u16(), body),
+            OrthancErrorCode::NotAcceptable,
+        ),
+        StatusCode::NOT_IMPLEMENTED => or_error(
+            send_http_status(context, output, code.as_u16(), body),
+            OrthancErrorCode::NotImplemented,
+        ),
+        StatusCode::BAD_REQUEST => or_error(
+            send_http_status(context, output, code.as_u16(), body),
+            OrthancErrorCode::BadRequest,
+        ),
         // note: OrthancPluginErrorCode_Timeout is *not* used for codes 408 nor 504
         _ => send_http_status(context, output, code.as_u16(), body),
     }
 }
 
+/// If `code` is a success, return `error` instead.
+fn or_error(
+    code: bindings::OrthancPluginErrorCode,
+    error: OrthancErrorCode,
+) -> bindings::OrthancPluginErrorCode {
+    OrthancErrorCode::from(code)
+        .into_result()
+        .map_or(code, |()| error.into())
+}
+
 /// Respond to an HTTP request without a body.
 ///
 /// Note: this function handles the "must use" logic required by Orthanc. See
@@ -92,32 +142,24 @@ fn respond_no_body(
     match code {
         StatusCode::OK => {
             answer_buffer(context, output, &[], c"text/plain");
-            bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_Success
+            OrthancErrorCode::Success.into()
         }
         StatusCode::MOVED_PERMANENTLY => {
             // TODO must use ::OrthancPluginRedirect()
-            bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_NotImplemented
+            OrthancErrorCode::NotImplemented.into()
         }
         StatusCode::UNAUTHORIZED => {
             // TODO must use ::OrthancPluginSendUnauthorized()
-            bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_NotImplemented
+            OrthancErrorCode::NotImplemented.into()
         }
         StatusCode::METHOD_NOT_ALLOWED => {
             // TODO must use ::OrthancPluginSendMethodNotAllowed()
-            bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_NotImplemented
-        }
-        StatusCode::NOT_ACCEPTABLE => {
-            bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_NotAcceptable
-        }
-        StatusCode::NOT_IMPLEMENTED => {
-            bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_NotImplemented
-        }
-        StatusCode::NOT_FOUND => {
-            bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_UnknownResource
-        }
-        StatusCode::BAD_REQUEST => {
-            bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_BadRequest
+            OrthancErrorCode::NotImplemented.into()
         }
+        StatusCode::NOT_ACCEPTABLE => OrthancErrorCode::NotAcceptable.into(),
+        StatusCode::NOT_IMPLEMENTED => OrthancErrorCode::NotImplemented.into(),
+        StatusCode::NOT_FOUND => OrthancErrorCode::UnknownResource.into(),
+        StatusCode::BAD_REQUEST => OrthancErrorCode::BadRequest.into(),
         // note: OrthancPluginErrorCode_Timeout is *not* used for codes 408 nor 504
         _ => send_http_status_code(context, output, code.as_u16()),
     }
@@ -12,6 +12,7 @@
 use std::ffi::{CStr, CString};
 
 use crate::bindings;
+use crate::error_code::OrthancErrorCode;
 
 /// Translation of the C code which appears as the last line of most functions in `OrthancCPlugin.h`,
 /// e.g. <https://orthanc.uclouvain.be/sdk/OrthancCPlugin_8h_source.html#l03056>
@@ -42,9 +43,9 @@ pub(crate) fn must_invoke_service<T>(
     caller: &'static str,
 ) {
     let code = invoke_service(context, service, params);
-    if code != bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_Success {
+    if let Err(e) = OrthancErrorCode::from(code).into_result() {
         panic!(
-            "Unsuccessful call to context->InvokeService in {}::sdk::{caller} (code {code})",
+            "Unsuccessful call to context->InvokeService in {}::sdk::{caller}: {e}",
             env!("CARGO_PKG_NAME")
         )
     }
@@ -179,6 +180,7 @@ pub(crate) fn answer_buffer(
 /// Sets a HTTP header in the HTTP answer.
 ///
 /// Translated from [`OrthancPluginSetHttpHeader`](https://orthanc.uclouvain.be/sdk/OrthancCPlugin_8h_source.html#l03149).
+#[cfg(feature = "webapp")]
 pub(crate) fn set_http_header(
     context: *mut bindings::OrthancPluginContext,
     output: *mut bindings::OrthancPluginRestOutput,
@@ -234,3 +236,154 @@ pub(crate) fn send_http_status_code(
         params,
     )
 }
+
+/// Declare a custom error code for this plugin. Returns the error code which has
+/// been assigned inside the Orthanc core, or the error code of the unsuccessful call.
+///
+/// When a REST callback returns the assigned error code, Orthanc answers with
+/// `http_status` and reports `message` in its standard error JSON.
+///
+/// Translated from [`OrthancPluginRegisterErrorCode`](https://orthanc.uclouvain.be/sdk/group__Toolbox.html).
+pub fn register_error_code(
+    context: *mut bindings::OrthancPluginContext,
+    code: i32,
+    http_status: http::StatusCode,
+    message: &str,
+) -> Result<OrthancErrorCode, OrthancErrorCode> {
+    let c_message = CString::new(message.replace('\0', "")).unwrap();
+    let mut target: bindings::OrthancPluginErrorCode =
+        bindings::OrthancPluginErrorCode_OrthancPluginErrorCode_Plugin;
+    let params = bindings::_OrthancPluginRegisterErrorCode {
+        target: &mut target,
+        code,
+        httpStatus: http_status.as_u16(),
+        message: c_message.as_ptr(),
+    };
+    let code = invoke_service(
+        context,
+        bindings::_OrthancPluginService__OrthancPluginService_RegisterErrorCode,
+        params,
+    );
+    OrthancErrorCode::from(code).into_result()?;
+    Ok(target.into())
+}
+
+/// Provide a detailed description for an HTTP error, which is reported in the
+/// `"Details"` field of the error JSON. If `log` is `true`, the error is also
+/// written to the Orthanc log.
+///
+/// Translated from [`OrthancPluginSetHttpErrorDetails`](https://orthanc.uclouvain.be/sdk/group__REST.html).
+pub fn set_http_error_details(
+    context: *mut bindings::OrthancPluginContext,
+    output: *mut bindings::OrthancPluginRestOutput,
+    details: &str,
+    log: bool,
+) -> Result<(), OrthancErrorCode> {
+    let c_details = CString::new(details.replace('\0', "")).unwrap();
+    let params = bindings::_OrthancPluginSetHttpErrorDetails {
+        output,
+        details: c_details.as_ptr(),
+        log: log as u8,
+    };
+    let code = invoke_service(
+        context,
+        bindings::_OrthancPluginService__OrthancPluginService_SetHttpErrorDetails,
+        params,
+    );
+    OrthancErrorCode::from(code).into_result()
+}
+
+/// An entry of the DICOM dictionary of Orthanc, see [lookup_dictionary].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct OrthancDictionaryEntry {
+    pub tag: orthanc_api::Tag,
+    pub vr: orthanc_api::Vr,
+    pub min_multiplicity: u32,
+    /// Maximum multiplicity, or 0 if the multiplicity is arbitrary.
+    pub max_multiplicity: u32,
+}
+
+/// Get the entry of a DICOM tag in the dictionary of Orthanc, given its keyword
+/// (e.g. `"AccessionNumber"`) or its tag (e.g. `"0008,0050"`).
+///
+/// Unlike [orthanc_api::dictionary], the dictionary of Orthanc includes every
+/// public tag and the private tags declared in its configuration.
+///
+/// Returns [OrthancErrorCode::ParameterOutOfRange] if `name` contains a NUL character.
+///
+/// Translated from [`OrthancPluginLookupDictionary`](https://orthanc.uclouvain.be/sdk/group__Toolbox.html).
+pub fn lookup_dictionary(
+    context: *mut bindings::OrthancPluginContext,
+    name: &str,
+) -> Result<OrthancDictionaryEntry, OrthancErrorCode> {
+    let c_name = CString::new(name).map_err(|_| OrthancErrorCode::ParameterOutOfRange)?;
+    let mut target = bindings::OrthancPluginDictionaryEntry {
+        group: 0,
+        element: 0,
+        vr: bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_UN,
+        minMultiplicity: 0,
+        maxMultiplicity: 0,
+    };
+    let params = bindings::_OrthancPluginLookupDictionary {
+        name: c_name.as_ptr(),
+        target: &mut target,
+    };
+    let code = invoke_service(
+        context,
+        bindings::_OrthancPluginService__OrthancPluginService_LookupDictionary,
+        params,
+    );
+    OrthancErrorCode::from(code).into_result()?;
+    Ok(OrthancDictionaryEntry {
+        tag: orthanc_api::Tag::new(target.group, target.element),
+        vr: vr_from_orthanc(target.vr),
+        min_multiplicity: target.minMultiplicity,
+        max_multiplicity: target.maxMultiplicity,
+    })
+}
+
+fn vr_from_orthanc(vr: bindings::OrthancPluginValueRepresentation) -> orthanc_api::Vr {
+    use orthanc_api::Vr;
+    match vr {
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_AE => Vr::AE,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_AS => Vr::AS,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_AT => Vr::AT,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_CS => Vr::CS,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_DA => Vr::DA,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_DS => Vr::DS,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_DT => Vr::DT,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_FD => Vr::FD,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_FL => Vr::FL,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_IS => Vr::IS,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_LO => Vr::LO,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_LT => Vr::LT,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_OB => Vr::OB,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_OF => Vr::OF,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_OW => Vr::OW,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_PN => Vr::PN,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_SH => Vr::SH,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_SL => Vr::SL,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_SQ => Vr::SQ,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_SS => Vr::SS,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_ST => Vr::ST,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_TM => Vr::TM,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_UI => Vr::UI,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_UL => Vr::UL,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_US => Vr::US,
+        bindings::OrthancPluginValueRepresentation_OrthancPluginValueRepresentation_UT => Vr::UT,
+        _ => Vr::UN,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_dictionary_nul() {
+        assert_eq!(
+            lookup_dictionary(std::ptr::null_mut(), "Accession\0Number"),
+            Err(OrthancErrorCode::ParameterOutOfRange)
+        );
+    }
+}
@@ -0,0 +1,302 @@
+use super::event::OnChangeEvent;
+use crate::panic::panic_message;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, SendError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// A thread which coalesces invocations to
+/// [OrthancPluginOnChangeCallback](https://orthanc.uclouvain.be/sdk/group__Callbacks.html#gabd05790b93ac3ef7d7e91e9d8bc46295)
+/// into batches.
+///
+/// Events are grouped by a key, which by default is their resource. A batch is
+/// delivered once no new event with the same key was received for the
+/// duration of `window`, or at the latest `max_wait` after its first event,
+/// so that a continuous stream of events is still delivered. Repeated events (e.g. the many `NewChildInstance`
+/// events of a study which is being uploaded) are delivered only once per
+/// batch, in the order they were first received.
+///
+/// Every `NewInstance` event has a different resource, so the burst of
+/// `NewInstance` events while a study arrives is only coalesced with a
+/// coarser key, see [OnChangeDebouncer::spawn_with_key].
+///
+/// ## Example
+///
+/// ```
+/// use orthanc_sdk::utils::{ChangeType, OnChangeDebouncer, OnChangeEvent};
+/// use std::time::Duration;
+///
+/// let debouncer = OnChangeDebouncer::spawn(
+///     Duration::from_secs(5),
+///     Duration::from_secs(60),
+///     |batch: Vec<OnChangeEvent>| {
+///         if batch.iter().any(|e| e.change_type == ChangeType::StableStudy) {
+///             // ... study-level work, done once per study ...
+///         }
+///     },
+/// );
+/// debouncer.join().unwrap();
+/// ```
+pub struct OnChangeDebouncer {
+    handle: JoinHandle<()>,
+    sender: Sender<OnChangeEvent>,
+}
+
+impl OnChangeDebouncer {
+    /// Spawn the thread.
+    ///
+    /// If `on_batch` panics, the panic is logged and the thread continues
+    /// with the next batch.
+    pub fn spawn<F: Fn(Vec<OnChangeEvent>) + Send + 'static>(
+        window: Duration,
+        max_wait: Duration,
+        on_batch: F,
+    ) -> Self {
+        Self::spawn_with_key(
+            window,
+            max_wait,
+            |event: &OnChangeEvent| (event.resource_type, event.resource_id.clone()),
+            on_batch,
+        )
+    }
+
+    /// Spawn the thread, grouping events by the value of `key`.
+    ///
+    /// For example, to get one batch for a burst of `NewInstance` events,
+    /// give them all the same key:
+    ///
+    /// ```
+    /// use orthanc_sdk::utils::{ChangeType, OnChangeDebouncer, OnChangeEvent};
+    /// use std::time::Duration;
+    ///
+    /// let debouncer = OnChangeDebouncer::spawn_with_key(
+    ///     Duration::from_secs(5),
+    ///     Duration::from_secs(60),
+    ///     |event: &OnChangeEvent| match event.change_type {
+    ///         ChangeType::NewInstance => None,
+    ///         _ => event.resource_id.clone(),
+    ///     },
+    ///     |batch: Vec<OnChangeEvent>| {
+    ///         // ... all the instances received in a burst ...
+    ///     },
+    /// );
+    /// debouncer.join().unwrap();
+    /// ```
+    ///
+    /// The key function is called in the debouncer's thread, so it may also
+    /// call the built-in API, e.g. to key `NewInstance` events by their parent study.
+    pub fn spawn_with_key<K, KF, F>(
+        window: Duration,
+        max_wait: Duration,
+        key: KF,
+        on_batch: F,
+    ) -> Self
+    where
+        K: Eq + Hash,
+        KF: Fn(&OnChangeEvent) -> K + Send + 'static,
+        F: Fn(Vec<OnChangeEvent>) + Send + 'static,
+    {
+        let (sender, rx) = std::sync::mpsc::channel();
+        let handle = std::thread::spawn(move || debounce(rx, window, max_wait, key, on_batch));
+        Self { handle, sender }
+    }
+
+    /// Send an event to the thread.
+    pub fn send(&self, event: OnChangeEvent) -> Result<(), SendError<OnChangeEvent>> {
+        self.sender.send(event)
+    }
+
+    /// Join this thread. Pending batches are delivered immediately,
+    /// without waiting for their window to elapse.
+    pub fn join(self) -> std::thread::Result<()> {
+        drop(self.sender);
+        self.handle.join()
+    }
+}
+
+/// The events of a resource which have not been delivered yet.
+struct Pending {
+    deadline: Instant,
+    /// Latest time of delivery, regardless of new events.
+    max_deadline: Instant,
+    events: Vec<OnChangeEvent>,
+    seen: HashSet<OnChangeEvent>,
+}
+
+fn debounce<K: Eq + Hash, KF: Fn(&OnChangeEvent) -> K, F: Fn(Vec<OnChangeEvent>)>(
+    rx: Receiver<OnChangeEvent>,
+    window: Duration,
+    max_wait: Duration,
+    key: KF,
+    on_batch: F,
+) {
+    let mut pending: HashMap<K, Pending> = HashMap::new();
+    loop {
+        let now = Instant::now();
+        let mut due: Vec<_> = pending
+            .extract_if(|_, p| p.deadline <= now)
+            .map(|(_, p)| p)
+            .collect();
+        due.sort_by_key(|p| p.deadline);
+        for batch in due {
+            deliver(&on_batch, batch.events);
+        }
+
+        let next_deadline = pending.values().map(|p| p.deadline).min();
+        let received = if let Some(deadline) = next_deadline {
+            rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+        } else {
+            rx.recv().map_err(|_| RecvTimeoutError::Disconnected)
+        };
+        match received {
+            Ok(event) => {
+                let now = Instant::now();
+                let batch = pending.entry(key(&event)).or_insert_with(|| Pending {
+                    deadline: now,
+                    max_deadline: now + max_wait,
+                    events: Vec::new(),
+                    seen: HashSet::new(),
+                });
+                batch.deadline = (now + window).min(batch.max_deadline);
+                if batch.seen.insert(event.clone()) {
+                    batch.events.push(event);
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => {
+                let mut rest: Vec<_> = pending.into_values().collect();
+                rest.sort_by_key(|p| p.deadline);
+                for batch in rest {
+                    deliver(&on_batch, batch.events);
+                }
+                break;
+            }
+        }
+    }
+}
+
+fn deliver<F: Fn(Vec<OnChangeEvent>)>(on_batch: &F, events: Vec<OnChangeEvent>) {
+    if let Err(payload) = catch_unwind(AssertUnwindSafe(|| on_batch(events))) {
+        tracing::error!(
+            panic = panic_message(payload.as_ref()),
+            "on change batch handler panicked"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{ChangeType, ResourceType};
+    use std::sync::{Arc, Mutex};
+
+    fn event(change_type: ChangeType, study: &str) -> OnChangeEvent {
+        OnChangeEvent {
+            change_type,
+            resource_type: ResourceType::Study,
+            resource_id: Some(study.to_string()),
+        }
+    }
+
+    fn spawn_recorder() -> (OnChangeDebouncer, Arc<Mutex<Vec<Vec<OnChangeEvent>>>>) {
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let debouncer = {
+            let batches = Arc::clone(&batches);
+            OnChangeDebouncer::spawn(
+                Duration::from_millis(50),
+                Duration::from_millis(500),
+                move |batch| batches.lock().unwrap().push(batch),
+            )
+        };
+        (debouncer, batches)
+    }
+
+    #[test]
+    fn test_coalesce_and_dedupe_by_resource() {
+        let (debouncer, batches) = spawn_recorder();
+        for _ in 0..10 {
+            debouncer
+                .send(event(ChangeType::NewChildInstance, "a"))
+                .unwrap();
+            debouncer
+                .send(event(ChangeType::NewChildInstance, "b"))
+                .unwrap();
+        }
+        debouncer.send(event(ChangeType::StableStudy, "a")).unwrap();
+        debouncer.join().unwrap();
+
+        let batches = batches.lock().unwrap();
+        assert_eq!(batches.len(), 2);
+        let a = batches
+            .iter()
+            .find(|b| b[0].resource_id.as_deref() == Some("a"))
+            .unwrap();
+        assert_eq!(
+            a,
+            &vec![
+                event(ChangeType::NewChildInstance, "a"),
+                event(ChangeType::StableStudy, "a")
+            ]
+        );
+        let b = batches
+            .iter()
+            .find(|b| b[0].resource_id.as_deref() == Some("b"))
+            .unwrap();
+        assert_eq!(b, &vec![event(ChangeType::NewChildInstance, "b")]);
+    }
+
+    #[test]
+    fn test_deliver_after_window() {
+        let (debouncer, batches) = spawn_recorder();
+        debouncer.send(event(ChangeType::StableStudy, "a")).unwrap();
+        std::thread::sleep(Duration::from_millis(200));
+        assert_eq!(batches.lock().unwrap().len(), 1);
+        debouncer.send(event(ChangeType::StableStudy, "a")).unwrap();
+        debouncer.join().unwrap();
+        assert_eq!(batches.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_coalesce_new_instances_with_key() {
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let debouncer = {
+            let batches = Arc::clone(&batches);
+            OnChangeDebouncer::spawn_with_key(
+                Duration::from_millis(50),
+                Duration::from_millis(500),
+                |event: &OnChangeEvent| event.change_type,
+                move |batch| batches.lock().unwrap().push(batch),
+            )
+        };
+        for i in 0..100 {
+            let event = OnChangeEvent {
+                change_type: ChangeType::NewInstance,
+                resource_type: ResourceType::Instance,
+                resource_id: Some(format!("instance-{i}")),
+            };
+            debouncer.send(event).unwrap();
+        }
+        debouncer.join().unwrap();
+
+        let batches = batches.lock().unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].len(), 100);
+    }
+
+    #[test]
+    fn test_deliver_continuous_stream_after_max_wait() {
+        let (debouncer, batches) = spawn_recorder();
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_millis(1200) {
+            debouncer
+                .send(event(ChangeType::NewChildInstance, "a"))
+                .unwrap();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let delivered = batches.lock().unwrap().len();
+        assert!(delivered >= 2, "delivered {delivered} batches");
+        debouncer.join().unwrap();
+    }
+}
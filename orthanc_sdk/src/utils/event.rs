@@ -0,0 +1,303 @@
+//! Rust-friendly types for the parameters of
+//! [OrthancPluginOnChangeCallback](https://orthanc.uclouvain.be/sdk/group__Callbacks.html#gabd05790b93ac3ef7d7e91e9d8bc46295).
+
+use crate::bindings;
+use orthanc_api::{InstanceId, JobId, PatientId, SeriesId, StudyId};
+use std::ffi::CStr;
+
+/// The event-specific parameters to
+/// [OrthancPluginOnChangeCallback](https://orthanc.uclouvain.be/sdk/group__Callbacks.html#gabd05790b93ac3ef7d7e91e9d8bc46295).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct OnChangeEvent {
+    pub change_type: ChangeType,
+    pub resource_type: ResourceType,
+    pub resource_id: Option<String>,
+}
+
+impl OnChangeEvent {
+    /// Convert the parameters of
+    /// [OrthancPluginOnChangeCallback](https://orthanc.uclouvain.be/sdk/group__Callbacks.html#gabd05790b93ac3ef7d7e91e9d8bc46295)
+    /// to safe Rust types.
+    ///
+    /// # Safety
+    ///
+    /// `resource_id` must be null or point to a nul-terminated string.
+    pub unsafe fn try_new(
+        change_type: bindings::OrthancPluginChangeType,
+        resource_type: bindings::OrthancPluginResourceType,
+        resource_id: *const std::ffi::c_char,
+    ) -> Result<Self, OnChangeEventError> {
+        let change_type = ChangeType::from(change_type);
+        let resource_type = ResourceType::from(resource_type);
+        let resource_id = if resource_id.is_null() {
+            None
+        } else {
+            let c_str = unsafe { CStr::from_ptr(resource_id) };
+            let s = c_str
+                .to_str()
+                .map_err(|_| OnChangeEventError::ResourceIdNotUtf8)?;
+            Some(s.to_string())
+        };
+        Ok(Self {
+            change_type,
+            resource_type,
+            resource_id,
+        })
+    }
+
+    /// Get the typed ID of the resource which has changed.
+    ///
+    /// Job events produce [ChangedResource::Job]. Other events produce an ID
+    /// according to [OnChangeEvent::resource_type], or [None] if the event is
+    /// not about a resource (e.g. [ChangeType::OrthancStarted]) or its
+    /// resource type is unknown.
+    pub fn resource(&self) -> Option<ChangedResource> {
+        let id = self.resource_id.clone()?;
+        if self.change_type.is_job() {
+            return Some(ChangedResource::Job(JobId::new(id)));
+        }
+        match self.resource_type {
+            ResourceType::Patient => Some(ChangedResource::Patient(PatientId::new(id))),
+            ResourceType::Study => Some(ChangedResource::Study(StudyId::new(id))),
+            ResourceType::Series => Some(ChangedResource::Series(SeriesId::new(id))),
+            ResourceType::Instance => Some(ChangedResource::Instance(InstanceId::new(id))),
+            ResourceType::None | ResourceType::Other(_) => None,
+        }
+    }
+}
+
+/// Error converting the parameters of
+/// [OrthancPluginOnChangeCallback](https://orthanc.uclouvain.be/sdk/group__Callbacks.html#gabd05790b93ac3ef7d7e91e9d8bc46295).
+#[derive(thiserror::Error, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum OnChangeEventError {
+    #[error("resource ID is not UTF-8")]
+    ResourceIdNotUtf8,
+}
+
+/// The type of change, translated from [`OrthancPluginChangeType`](bindings::OrthancPluginChangeType).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ChangeType {
+    /// Series is now complete
+    CompletedSeries,
+    /// Deleted resource
+    Deleted,
+    /// A new instance was added to this resource
+    NewChildInstance,
+    /// New instance received
+    NewInstance,
+    /// New patient created
+    NewPatient,
+    /// New series created
+    NewSeries,
+    /// New study created
+    NewStudy,
+    /// Timeout: No new instance in this patient
+    StablePatient,
+    /// Timeout: No new instance in this series
+    StableSeries,
+    /// Timeout: No new instance in this study
+    StableStudy,
+    /// Orthanc has started
+    OrthancStarted,
+    /// Orthanc is stopping
+    OrthancStopped,
+    /// Some user-defined attachment has changed for this resource
+    UpdatedAttachment,
+    /// Some user-defined metadata has changed for this resource
+    UpdatedMetadata,
+    /// The list of Orthanc peers has changed
+    UpdatedPeers,
+    /// The list of DICOM modalities has changed
+    UpdatedModalities,
+    /// New Job submitted
+    JobSubmitted,
+    /// A Job has completed successfully
+    JobSuccess,
+    /// A Job has failed
+    JobFailure,
+    /// A change type which is unknown to this crate, e.g. one added by a
+    /// newer version of Orthanc
+    Other(bindings::OrthancPluginChangeType),
+}
+
+impl ChangeType {
+    /// Returns `true` if this change is about a job, in which case the
+    /// resource ID is a [JobId].
+    pub fn is_job(&self) -> bool {
+        matches!(
+            self,
+            ChangeType::JobSubmitted | ChangeType::JobSuccess | ChangeType::JobFailure
+        )
+    }
+}
+
+impl From<bindings::OrthancPluginChangeType> for ChangeType {
+    fn from(value: bindings::OrthancPluginChangeType) -> Self {
+        match value {
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_CompletedSeries => {
+                Self::CompletedSeries
+            }
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_Deleted => Self::Deleted,
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_NewChildInstance => {
+                Self::NewChildInstance
+            }
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_NewInstance => {
+                Self::NewInstance
+            }
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_NewPatient => {
+                Self::NewPatient
+            }
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_NewSeries => Self::NewSeries,
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_NewStudy => Self::NewStudy,
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_StablePatient => {
+                Self::StablePatient
+            }
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_StableSeries => {
+                Self::StableSeries
+            }
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_StableStudy => {
+                Self::StableStudy
+            }
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_OrthancStarted => {
+                Self::OrthancStarted
+            }
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_OrthancStopped => {
+                Self::OrthancStopped
+            }
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_UpdatedAttachment => {
+                Self::UpdatedAttachment
+            }
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_UpdatedMetadata => {
+                Self::UpdatedMetadata
+            }
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_UpdatedPeers => {
+                Self::UpdatedPeers
+            }
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_UpdatedModalities => {
+                Self::UpdatedModalities
+            }
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_JobSubmitted => {
+                Self::JobSubmitted
+            }
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_JobSuccess => {
+                Self::JobSuccess
+            }
+            bindings::OrthancPluginChangeType_OrthancPluginChangeType_JobFailure => {
+                Self::JobFailure
+            }
+            other => Self::Other(other),
+        }
+    }
+}
+
+/// The type of resource, translated from [`OrthancPluginResourceType`](bindings::OrthancPluginResourceType).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ResourceType {
+    /// Patient
+    Patient,
+    /// Study
+    Study,
+    /// Series
+    Series,
+    /// Instance
+    Instance,
+    /// Unavailable resource type
+    None,
+    /// A resource type which is unknown to this crate
+    Other(bindings::OrthancPluginResourceType),
+}
+
+impl From<bindings::OrthancPluginResourceType> for ResourceType {
+    fn from(value: bindings::OrthancPluginResourceType) -> Self {
+        match value {
+            bindings::OrthancPluginResourceType_OrthancPluginResourceType_Patient => Self::Patient,
+            bindings::OrthancPluginResourceType_OrthancPluginResourceType_Study => Self::Study,
+            bindings::OrthancPluginResourceType_OrthancPluginResourceType_Series => Self::Series,
+            bindings::OrthancPluginResourceType_OrthancPluginResourceType_Instance => {
+                Self::Instance
+            }
+            bindings::OrthancPluginResourceType_OrthancPluginResourceType_None => Self::None,
+            other => Self::Other(other),
+        }
+    }
+}
+
+/// Typed ID of the resource of an [OnChangeEvent].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ChangedResource {
+    Patient(PatientId),
+    Study(StudyId),
+    Series(SeriesId),
+    Instance(InstanceId),
+    Job(JobId),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_type_from() {
+        assert_eq!(
+            ChangeType::from(bindings::OrthancPluginChangeType_OrthancPluginChangeType_StableStudy),
+            ChangeType::StableStudy
+        );
+        assert_eq!(
+            ChangeType::from(bindings::OrthancPluginChangeType_OrthancPluginChangeType_JobFailure),
+            ChangeType::JobFailure
+        );
+        assert_eq!(ChangeType::from(9999), ChangeType::Other(9999));
+    }
+
+    #[test]
+    fn test_resource_type_from() {
+        assert_eq!(
+            ResourceType::from(
+                bindings::OrthancPluginResourceType_OrthancPluginResourceType_Series
+            ),
+            ResourceType::Series
+        );
+        assert_eq!(
+            ResourceType::from(bindings::OrthancPluginResourceType_OrthancPluginResourceType_None),
+            ResourceType::None
+        );
+        assert_eq!(ResourceType::from(9999), ResourceType::Other(9999));
+    }
+
+    fn event(
+        change_type: ChangeType,
+        resource_type: ResourceType,
+        resource_id: Option<&str>,
+    ) -> OnChangeEvent {
+        OnChangeEvent {
+            change_type,
+            resource_type,
+            resource_id: resource_id.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_resource() {
+        let id = "e1df078e-9b2bd072-b24090ad-2ad9c9c9-16c96754";
+        assert_eq!(
+            event(ChangeType::StableStudy, ResourceType::Study, Some(id)).resource(),
+            Some(ChangedResource::Study(StudyId::new(id)))
+        );
+        assert_eq!(
+            event(ChangeType::NewInstance, ResourceType::Instance, Some(id)).resource(),
+            Some(ChangedResource::Instance(InstanceId::new(id)))
+        );
+        assert_eq!(
+            event(ChangeType::JobSuccess, ResourceType::None, Some(id)).resource(),
+            Some(ChangedResource::Job(JobId::new(id)))
+        );
+        assert_eq!(
+            event(ChangeType::OrthancStarted, ResourceType::None, None).resource(),
+            None
+        );
+        assert_eq!(
+            event(ChangeType::Other(9999), ResourceType::Other(9999), Some(id)).resource(),
+            None
+        );
+    }
+}
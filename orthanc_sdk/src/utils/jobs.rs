@@ -0,0 +1,421 @@
+use super::event::{ChangeType, ChangedResource, OnChangeEvent};
+use crate::api::{GeneralClient, ResponseError};
+use crate::bindings::OrthancPluginContext;
+use crate::panic::panic_message;
+use orthanc_api::{JobId, JobInfo};
+use std::collections::HashMap;
+use std::future::Future;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Calls a callback when an Orthanc job finishes.
+///
+/// [JobWatcher::handle] should be called for every event of
+/// [OrthancPluginOnChangeCallback](https://orthanc.uclouvain.be/sdk/group__Callbacks.html#gabd05790b93ac3ef7d7e91e9d8bc46295),
+/// e.g. from the function given to [OnChangeThread::spawn](super::OnChangeThread::spawn).
+/// When a `JobSuccess` or `JobFailure` event is received for a watched job,
+/// its [JobInfo] is fetched and passed to the job's callback. Check
+/// [JobInfo::state] to know whether the job was successful.
+///
+/// A background thread calls the callbacks of jobs which time out.
+///
+/// ## Example
+///
+/// ```no_run
+/// use orthanc_sdk::api::types::{JobId, JobState};
+/// use orthanc_sdk::utils::JobWatcher;
+/// use std::time::Duration;
+///
+/// # fn anonymize_study() -> Result<JobId, ()> {
+/// #     Ok(JobId::new("0b09cfb2-d5c3-4340-9f96-0ae8812eadfe".to_string()))
+/// # }
+/// let watcher = JobWatcher::spawn();
+/// let submitted = watcher.submit(
+///     || anonymize_study(),
+///     Some(Duration::from_secs(600)),
+///     |result| match result {
+///         Ok(job) if job.state == JobState::Success => tracing::info!("study was anonymized"),
+///         Ok(job) => tracing::error!(error = job.error_description, "anonymization failed"),
+///         Err(e) => tracing::error!("{e}"),
+///     },
+/// );
+/// ```
+pub struct JobWatcher {
+    shared: Arc<Shared>,
+    handle: JoinHandle<()>,
+}
+
+/// Callback of a watched job.
+pub type JobCallback = Box<dyn FnOnce(Result<JobInfo, JobWatchError>) + Send>;
+
+struct Watch {
+    deadline: Option<Instant>,
+    callback: JobCallback,
+}
+
+#[derive(Default)]
+struct State {
+    watches: HashMap<JobId, Watch>,
+    stopped: bool,
+    /// Number of calls to [JobWatcher::submit] which are submitting a job.
+    submitting: usize,
+    /// Results of jobs which finished while being submitted.
+    finished_early: HashMap<JobId, Result<JobInfo, JobWatchError>>,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl JobWatcher {
+    /// Create a [JobWatcher], spawning its background thread for timeouts.
+    pub fn spawn() -> Self {
+        let shared = Arc::new(Shared::default());
+        let handle = {
+            let shared = Arc::clone(&shared);
+            std::thread::spawn(move || expire_timeouts(&shared))
+        };
+        Self { shared, handle }
+    }
+
+    /// Call `callback` when the job finishes or after `timeout` has elapsed.
+    ///
+    /// A job which finishes before it is watched is never reported. To watch a
+    /// job which is being submitted, use [JobWatcher::submit] instead.
+    pub fn watch<F>(&self, id: JobId, timeout: Option<Duration>, callback: F)
+    where
+        F: FnOnce(Result<JobInfo, JobWatchError>) + Send + 'static,
+    {
+        let rejected = self.insert(&mut self.shared.lock(), id, timeout, Box::new(callback));
+        if let Some((callback, error)) = rejected {
+            deliver(callback, Err(error));
+        }
+    }
+
+    /// Submit a job using `submit` then watch it like [JobWatcher::watch].
+    ///
+    /// Jobs which finish while `submit` runs are remembered by
+    /// [JobWatcher::handle], so it is not possible to miss the job finishing.
+    pub fn submit<S, E, F>(
+        &self,
+        submit: S,
+        timeout: Option<Duration>,
+        callback: F,
+    ) -> Result<JobId, E>
+    where
+        S: FnOnce() -> Result<JobId, E>,
+        F: FnOnce(Result<JobInfo, JobWatchError>) + Send + 'static,
+    {
+        self.shared.lock().submitting += 1;
+        let submitted = submit();
+        let mut state = self.shared.lock();
+        state.submitting -= 1;
+        let finished = submitted
+            .as_ref()
+            .ok()
+            .and_then(|id| state.finished_early.remove(id));
+        if state.submitting == 0 {
+            state.finished_early.clear();
+        }
+        let id = submitted?;
+        let callback: JobCallback = Box::new(callback);
+        let rejected = if let Some(result) = finished {
+            Some((callback, result))
+        } else {
+            self.insert(&mut state, id.clone(), timeout, callback)
+                .map(|(callback, error)| (callback, Err(error)))
+        };
+        drop(state);
+        if let Some((callback, result)) = rejected {
+            deliver(callback, result);
+        }
+        Ok(id)
+    }
+
+    /// Get a future which resolves when the job finishes or after `timeout` has elapsed.
+    ///
+    /// The same caveat as [JobWatcher::watch] applies.
+    pub fn wait(&self, id: JobId, timeout: Option<Duration>) -> JobFuture {
+        let slot = Arc::new(Mutex::new(Slot::default()));
+        let future = JobFuture {
+            slot: Arc::clone(&slot),
+        };
+        self.watch(id, timeout, move |result| {
+            let mut slot = slot.lock().unwrap_or_else(PoisonError::into_inner);
+            slot.result = Some(result);
+            if let Some(waker) = slot.waker.take() {
+                waker.wake();
+            }
+        });
+        future
+    }
+
+    /// Add a watch, returning the callback which must be called with an error
+    /// (if any). Callbacks must not be called while the lock is held.
+    fn insert(
+        &self,
+        state: &mut State,
+        id: JobId,
+        timeout: Option<Duration>,
+        callback: JobCallback,
+    ) -> Option<(JobCallback, JobWatchError)> {
+        if state.stopped {
+            return Some((callback, JobWatchError::Stopped));
+        }
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let watch = Watch { deadline, callback };
+        let previous = state.watches.insert(id, watch);
+        self.shared.changed.notify_one();
+        previous.map(|previous| (previous.callback, JobWatchError::Replaced))
+    }
+
+    /// Handle an event from
+    /// [OrthancPluginOnChangeCallback](https://orthanc.uclouvain.be/sdk/group__Callbacks.html#gabd05790b93ac3ef7d7e91e9d8bc46295).
+    ///
+    /// Returns `true` if the event was about a watched job.
+    pub fn handle(&self, context: *mut OrthancPluginContext, event: &OnChangeEvent) -> bool {
+        if !matches!(
+            event.change_type,
+            ChangeType::JobSuccess | ChangeType::JobFailure
+        ) {
+            return false;
+        }
+        let Some(ChangedResource::Job(id)) = event.resource() else {
+            return false;
+        };
+        self.finished(id, |id| {
+            GeneralClient::new(context)
+                .get(id)
+                .ok_data()
+                .map_err(|e| JobWatchError::Response(Box::new(e)))
+        })
+    }
+
+    /// Deliver the result of a job which finished, fetched by `fetch`.
+    fn finished<F>(&self, id: JobId, fetch: F) -> bool
+    where
+        F: FnOnce(JobId) -> Result<JobInfo, JobWatchError>,
+    {
+        let watch = {
+            let mut state = self.shared.lock();
+            let watch = state.watches.remove(&id);
+            if watch.is_none() && state.submitting == 0 {
+                return false;
+            }
+            watch
+        };
+        let result = fetch(id.clone());
+        // The job might be the one being submitted by JobWatcher::submit,
+        // which might have started watching it in the meantime.
+        let watch = match watch {
+            Some(watch) => watch,
+            None => {
+                let mut state = self.shared.lock();
+                match state.watches.remove(&id) {
+                    Some(watch) => watch,
+                    None => {
+                        if state.submitting > 0 {
+                            state.finished_early.insert(id, result);
+                        }
+                        return false;
+                    }
+                }
+            }
+        };
+        deliver(watch.callback, result);
+        true
+    }
+
+    /// Stop the background thread. The callbacks of jobs which are still
+    /// being watched are called with [JobWatchError::Stopped].
+    pub fn join(self) -> std::thread::Result<()> {
+        let watches = {
+            let mut state = self.shared.lock();
+            state.stopped = true;
+            std::mem::take(&mut state.watches)
+        };
+        self.shared.changed.notify_one();
+        for watch in watches.into_values() {
+            deliver(watch.callback, Err(JobWatchError::Stopped));
+        }
+        self.handle.join()
+    }
+}
+
+/// Call the callbacks of watches which timed out, until the watcher is stopped.
+fn expire_timeouts(shared: &Shared) {
+    let mut state = shared.lock();
+    while !state.stopped {
+        let now = Instant::now();
+        let expired: Vec<_> = state
+            .watches
+            .extract_if(|_, watch| watch.deadline.is_some_and(|deadline| deadline <= now))
+            .map(|(_, watch)| watch)
+            .collect();
+        if !expired.is_empty() {
+            drop(state);
+            for watch in expired {
+                deliver(watch.callback, Err(JobWatchError::Timeout));
+            }
+            state = shared.lock();
+            continue;
+        }
+        let next_deadline = state
+            .watches
+            .values()
+            .filter_map(|watch| watch.deadline)
+            .min();
+        state = if let Some(deadline) = next_deadline {
+            shared
+                .changed
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0
+        } else {
+            shared
+                .changed
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner)
+        };
+    }
+}
+
+fn deliver(callback: JobCallback, result: Result<JobInfo, JobWatchError>) {
+    if let Err(payload) = catch_unwind(AssertUnwindSafe(|| callback(result))) {
+        tracing::error!(
+            panic = panic_message(payload.as_ref()),
+            "job callback panicked"
+        );
+    }
+}
+
+/// Error watching an Orthanc job.
+#[derive(thiserror::Error, Debug)]
+pub enum JobWatchError {
+    /// The job did not finish before the timeout.
+    #[error("timed out waiting for job to finish")]
+    Timeout,
+    /// The job was watched again with another callback.
+    #[error("job is being watched by another callback")]
+    Replaced,
+    /// The [JobWatcher] was stopped before the job finished.
+    #[error("job watcher was stopped")]
+    Stopped,
+    /// The job finished, but its information could not be fetched.
+    #[error("cannot get information of finished job: {0}")]
+    Response(Box<ResponseError<JobInfo>>),
+}
+
+#[derive(Default)]
+struct Slot {
+    result: Option<Result<JobInfo, JobWatchError>>,
+    waker: Option<Waker>,
+}
+
+/// Future returned by [JobWatcher::wait].
+pub struct JobFuture {
+    slot: Arc<Mutex<Slot>>,
+}
+
+impl Future for JobFuture {
+    type Output = Result<JobInfo, JobWatchError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.slot.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(result) = slot.result.take() {
+            Poll::Ready(result)
+        } else {
+            slot.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    fn job_id(s: &str) -> JobId {
+        JobId::new(s.to_string())
+    }
+
+    #[test]
+    fn test_timeout() {
+        let watcher = JobWatcher::spawn();
+        let (tx, rx) = mpsc::channel();
+        watcher.watch(
+            job_id("a"),
+            Some(Duration::from_millis(10)),
+            move |result| tx.send(result).unwrap(),
+        );
+        let result = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(result, Err(JobWatchError::Timeout)));
+        watcher.join().unwrap();
+    }
+
+    #[test]
+    fn test_join_stops_watches() {
+        let watcher = JobWatcher::spawn();
+        let (tx, rx) = mpsc::channel();
+        let submitted = watcher.submit(
+            || Ok::<_, ()>(job_id("a")),
+            None,
+            move |result| tx.send(result).unwrap(),
+        );
+        assert_eq!(submitted, Ok(job_id("a")));
+        let mut future = std::pin::pin!(watcher.wait(job_id("b"), None));
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(future.as_mut().poll(&mut cx).is_pending());
+        watcher.join().unwrap();
+        assert!(matches!(rx.recv().unwrap(), Err(JobWatchError::Stopped)));
+        assert!(matches!(
+            future.as_mut().poll(&mut cx),
+            Poll::Ready(Err(JobWatchError::Stopped))
+        ));
+    }
+
+    #[test]
+    fn test_submit_job_which_finishes_during_submit() {
+        let watcher = JobWatcher::spawn();
+        let (tx, rx) = mpsc::channel();
+        let submitted = watcher.submit(
+            || {
+                // the event is handled before submit returns, without deadlock
+                assert!(!watcher.finished(job_id("a"), |_| Err(JobWatchError::Replaced)));
+                Ok::<_, ()>(job_id("a"))
+            },
+            None,
+            move |result| tx.send(result).unwrap(),
+        );
+        assert_eq!(submitted, Ok(job_id("a")));
+        let result = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(result, Err(JobWatchError::Replaced)));
+        assert!(watcher.shared.lock().finished_early.is_empty());
+        watcher.join().unwrap();
+    }
+
+    #[test]
+    fn test_handle_ignores_unwatched_jobs() {
+        let watcher = JobWatcher::spawn();
+        let event = OnChangeEvent {
+            change_type: ChangeType::JobSuccess,
+            resource_type: crate::utils::ResourceType::None,
+            resource_id: Some("a".to_string()),
+        };
+        assert!(!watcher.handle(std::ptr::null_mut(), &event));
+        watcher.join().unwrap();
+    }
+}
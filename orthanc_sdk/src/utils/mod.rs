@@ -0,0 +1,31 @@
+//! Miscellaneous utilities.
+//!
+//! Helpers for implementing [OrthancPluginRegisterOnChangeCallback](https://orthanc.uclouvain.be/sdk/group__Callbacks.html#ga78140887a94f1afb067a15db5ee4099c).
+//! The upstream documentation recommends handling events in a separate thread. This module helps with that:
+//!
+//! - [OnChangeThread] handles all events serially in one background thread.
+//! - [OnChangePool] handles events concurrently in a pool of threads, preserving
+//!   the order of events for the same resource.
+//! - `OnChangeRuntime` (requires the `tokio` feature) handles events using an
+//!   `async` function on a [tokio](https://tokio.rs) runtime.
+//!
+//! [OnChangeDebouncer] coalesces events of the same resource into batches,
+//! e.g. to do study-level work once instead of once per received instance.
+//!
+//! [JobWatcher] calls a callback (or resolves a future) when a job finishes.
+
+mod debounce;
+mod event;
+mod jobs;
+mod pool;
+#[cfg(feature = "tokio")]
+mod runtime;
+mod thread;
+
+pub use debounce::*;
+pub use event::*;
+pub use jobs::*;
+pub use pool::*;
+#[cfg(feature = "tokio")]
+pub use runtime::*;
+pub use thread::*;
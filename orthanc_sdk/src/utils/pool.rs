@@ -0,0 +1,195 @@
+use super::event::OnChangeEvent;
+use super::thread::supervise;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+use std::sync::mpsc::{SendError, SyncSender, TrySendError};
+use std::thread::JoinHandle;
+
+/// A pool of threads for handling invocations to
+/// [OrthancPluginOnChangeCallback](https://orthanc.uclouvain.be/sdk/group__Callbacks.html#gabd05790b93ac3ef7d7e91e9d8bc46295).
+///
+/// Events are assigned to workers by their resource ID, so events of the same
+/// resource are handled in the order they were sent. Events without a resource
+/// ID (e.g. [ChangeType::OrthancStarted](super::ChangeType::OrthancStarted))
+/// are all handled by the first worker.
+///
+/// Each worker has a bounded channel. [OnChangePool::send] blocks while the
+/// channel is full, which applies backpressure to Orthanc.
+pub struct OnChangePool {
+    workers: Vec<Worker>,
+}
+
+struct Worker {
+    sender: SyncSender<OnChangeEvent>,
+    handle: JoinHandle<()>,
+}
+
+impl OnChangePool {
+    /// Spawn `workers` threads, each having a channel which buffers up to
+    /// `capacity` events.
+    ///
+    /// Like [OnChangeThread](super::OnChangeThread), a worker is restarted
+    /// if `on_change` panics.
+    ///
+    /// ### Panics
+    ///
+    /// Panics if `workers` is zero.
+    pub fn spawn<F: Fn(OnChangeEvent) + Send + Sync + 'static>(
+        workers: usize,
+        capacity: usize,
+        on_change: F,
+    ) -> Self {
+        assert!(workers > 0, "OnChangePool must have at least one worker");
+        let on_change = Arc::new(on_change);
+        let workers = (0..workers)
+            .map(|_| {
+                let (sender, rx) = std::sync::mpsc::sync_channel(capacity);
+                let on_change = Arc::clone(&on_change);
+                let handle = std::thread::spawn(move || supervise(rx, on_change.as_ref()));
+                Worker { sender, handle }
+            })
+            .collect();
+        Self { workers }
+    }
+
+    /// Send an event to its worker, blocking while the worker's channel is full.
+    pub fn send(&self, event: OnChangeEvent) -> Result<(), SendError<OnChangeEvent>> {
+        self.worker_of(&event).sender.send(event)
+    }
+
+    /// Send an event to its worker without blocking.
+    pub fn try_send(&self, event: OnChangeEvent) -> Result<(), TrySendError<OnChangeEvent>> {
+        self.worker_of(&event).sender.try_send(event)
+    }
+
+    fn worker_of(&self, event: &OnChangeEvent) -> &Worker {
+        &self.workers[lane_of(event, self.workers.len())]
+    }
+
+    /// Stop accepting events, wait for all workers to finish handling the
+    /// events which were already sent, then join all workers.
+    pub fn join(self) -> std::thread::Result<()> {
+        // all senders must be dropped before joining any worker
+        let handles: Vec<_> = self
+            .workers
+            .into_iter()
+            .map(|Worker { sender, handle }| {
+                drop(sender);
+                handle
+            })
+            .collect();
+        // join every worker even if an earlier one failed
+        let mut result = Ok(());
+        for handle in handles {
+            let joined = handle.join();
+            if result.is_ok() {
+                result = joined;
+            }
+        }
+        result
+    }
+}
+
+/// Choose one of `n` lanes for an event. Events of the same resource are
+/// always assigned to the same lane.
+pub(super) fn lane_of(event: &OnChangeEvent, n: usize) -> usize {
+    if let Some(id) = &event.resource_id {
+        let mut hasher = DefaultHasher::new();
+        id.hash(&mut hasher);
+        (hasher.finish() % n as u64) as usize
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{ChangeType, ResourceType};
+    use std::sync::Mutex;
+
+    fn new_instance(series: usize, instance: usize) -> OnChangeEvent {
+        OnChangeEvent {
+            change_type: ChangeType::NewInstance,
+            resource_type: ResourceType::Instance,
+            resource_id: Some(format!("{series}/{instance}")),
+        }
+    }
+
+    #[test]
+    fn test_join_drains_events_in_order_per_resource() {
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let pool = {
+            let handled = Arc::clone(&handled);
+            OnChangePool::spawn(4, 2, move |event: OnChangeEvent| {
+                handled.lock().unwrap().push(event);
+            })
+        };
+        let sequence = [
+            ChangeType::NewSeries,
+            ChangeType::NewChildInstance,
+            ChangeType::StableSeries,
+            ChangeType::CompletedSeries,
+        ];
+        let events: Vec<_> = sequence
+            .into_iter()
+            .flat_map(|change_type| {
+                (0..10).map(move |series| OnChangeEvent {
+                    change_type,
+                    resource_type: ResourceType::Series,
+                    resource_id: Some(series.to_string()),
+                })
+            })
+            .collect();
+        for event in events.iter().cloned() {
+            pool.send(event).unwrap();
+        }
+        pool.join().unwrap();
+
+        let handled = handled.lock().unwrap();
+        assert_eq!(handled.len(), events.len());
+        for series in 0..10 {
+            let id = series.to_string();
+            let actual: Vec<_> = handled
+                .iter()
+                .filter(|e| e.resource_id.as_ref() == Some(&id))
+                .map(|e| e.change_type)
+                .collect();
+            assert_eq!(actual, sequence);
+        }
+    }
+
+    #[test]
+    fn test_worker_survives_panic() {
+        let handled = Arc::new(Mutex::new(0));
+        let pool = {
+            let handled = Arc::clone(&handled);
+            OnChangePool::spawn(1, 0, move |event| {
+                if event.resource_id.as_deref() == Some("0/0") {
+                    panic!("expected panic");
+                }
+                *handled.lock().unwrap() += 1;
+            })
+        };
+        pool.send(new_instance(0, 0)).unwrap();
+        pool.send(new_instance(0, 1)).unwrap();
+        pool.join().unwrap();
+        assert_eq!(*handled.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_lane_of_same_resource() {
+        let a = new_instance(1, 1);
+        let b = OnChangeEvent {
+            change_type: ChangeType::StableSeries,
+            ..a.clone()
+        };
+        assert_eq!(lane_of(&a, 7), lane_of(&b, 7));
+        let none = OnChangeEvent {
+            change_type: ChangeType::OrthancStarted,
+            resource_type: ResourceType::None,
+            resource_id: None,
+        };
+        assert_eq!(lane_of(&none, 7), 0);
+    }
+}
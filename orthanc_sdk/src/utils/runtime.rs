@@ -0,0 +1,143 @@
+use super::event::OnChangeEvent;
+use super::pool::lane_of;
+use crate::panic::panic_message;
+use std::future::Future;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use tokio::sync::mpsc;
+
+/// A [tokio] runtime for handling invocations to
+/// [OrthancPluginOnChangeCallback](https://orthanc.uclouvain.be/sdk/group__Callbacks.html#gabd05790b93ac3ef7d7e91e9d8bc46295)
+/// with an `async` function.
+///
+/// The runtime is driven by a background thread. Like [OnChangePool](super::OnChangePool),
+/// events are assigned to lanes by their resource ID: events of the same resource are
+/// handled in the order they were sent, whereas events in different lanes are handled
+/// concurrently. Each lane has a bounded channel, so [OnChangeRuntime::send] blocks
+/// while the lane is full.
+pub struct OnChangeRuntime {
+    senders: Vec<mpsc::Sender<OnChangeEvent>>,
+    handle: JoinHandle<()>,
+}
+
+impl OnChangeRuntime {
+    /// Start a multi-threaded [tokio] runtime with `lanes` lanes, each having
+    /// a channel which buffers up to `capacity` events.
+    ///
+    /// If the future produced by `on_change` panics, the panic is logged and
+    /// the lane continues with the next event.
+    ///
+    /// ### Panics
+    ///
+    /// Panics if `lanes` or `capacity` is zero.
+    pub fn spawn<F, Fut>(lanes: usize, capacity: usize, on_change: F) -> std::io::Result<Self>
+    where
+        F: Fn(OnChangeEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        assert!(lanes > 0, "OnChangeRuntime must have at least one lane");
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .thread_name("orthanc-on-change")
+            .build()?;
+        let on_change = Arc::new(on_change);
+        let (senders, receivers): (Vec<_>, Vec<_>) =
+            (0..lanes).map(|_| mpsc::channel(capacity)).unzip();
+        let handle = std::thread::spawn(move || {
+            runtime.block_on(async move {
+                let lanes: Vec<_> = receivers
+                    .into_iter()
+                    .map(|rx| tokio::spawn(run_lane(rx, Arc::clone(&on_change))))
+                    .collect();
+                for lane in lanes {
+                    let _ = lane.await;
+                }
+            })
+        });
+        Ok(Self { senders, handle })
+    }
+
+    /// Send an event to its lane, blocking while the lane's channel is full.
+    ///
+    /// ### Panics
+    ///
+    /// This function panics if called within an asynchronous execution context.
+    pub fn send(&self, event: OnChangeEvent) -> Result<(), mpsc::error::SendError<OnChangeEvent>> {
+        self.sender_of(&event).blocking_send(event)
+    }
+
+    /// Send an event to its lane without blocking.
+    pub fn try_send(
+        &self,
+        event: OnChangeEvent,
+    ) -> Result<(), mpsc::error::TrySendError<OnChangeEvent>> {
+        self.sender_of(&event).try_send(event)
+    }
+
+    fn sender_of(&self, event: &OnChangeEvent) -> &mpsc::Sender<OnChangeEvent> {
+        &self.senders[lane_of(event, self.senders.len())]
+    }
+
+    /// Stop accepting events, wait for the events which were already sent
+    /// to be handled, then shut down the runtime.
+    pub fn join(self) -> std::thread::Result<()> {
+        drop(self.senders);
+        self.handle.join()
+    }
+}
+
+/// Handle the events of one lane sequentially.
+async fn run_lane<F, Fut>(mut rx: mpsc::Receiver<OnChangeEvent>, on_change: Arc<F>)
+where
+    F: Fn(OnChangeEvent) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    while let Some(event) = rx.recv().await {
+        // each event is handled in its own task so that a panic is contained
+        let on_change = Arc::clone(&on_change);
+        let task = tokio::spawn(async move { on_change(event).await });
+        if let Err(e) = task.await
+            && e.is_panic()
+        {
+            tracing::error!(
+                panic = panic_message(e.into_panic().as_ref()),
+                "on change handler panicked"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{ChangeType, ResourceType};
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_join_drains_events() {
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let runtime = {
+            let handled = Arc::clone(&handled);
+            OnChangeRuntime::spawn(2, 1, move |event: OnChangeEvent| {
+                let handled = Arc::clone(&handled);
+                async move {
+                    if event.resource_id.as_deref() == Some("0") {
+                        panic!("expected panic");
+                    }
+                    handled.lock().unwrap().push(event.resource_id.unwrap());
+                }
+            })
+            .unwrap()
+        };
+        for i in 0..10 {
+            let event = OnChangeEvent {
+                change_type: ChangeType::StableStudy,
+                resource_type: ResourceType::Study,
+                resource_id: Some(i.to_string()),
+            };
+            runtime.send(event).unwrap();
+        }
+        runtime.join().unwrap();
+        assert_eq!(handled.lock().unwrap().len(), 9);
+    }
+}
@@ -1,22 +1,9 @@
-//! Miscellaneous utilities.
-//!
-//! Helpers for implementing [OrthancPluginRegisterOnChangeCallback](https://orthanc.uclouvain.be/sdk/group__Callbacks.html#ga78140887a94f1afb067a15db5ee4099c).
-//! The upstream documentation recommends handling events in a separate thread. This module helps with that.
-//!
-//! Future work: thread pool, async runtime, ...
-
-use crate::bindings;
-use std::sync::mpsc::{SendError, Sender};
+use super::event::OnChangeEvent;
+use crate::panic::panic_message;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::mpsc::{Receiver, SendError, Sender};
 use std::thread::JoinHandle;
 
-/// The event-specific parameters to
-/// [OrthancPluginOnChangeCallback](https://orthanc.uclouvain.be/sdk/group__Callbacks.html#gabd05790b93ac3ef7d7e91e9d8bc46295).
-pub struct OnChangeEvent {
-    pub change_type: bindings::OrthancPluginChangeType,
-    pub resource_type: bindings::OrthancPluginResourceType,
-    pub resource_id: Option<String>,
-}
-
 /// A thread and channel for handling invocations to
 /// [OrthancPluginOnChangeCallback](https://orthanc.uclouvain.be/sdk/group__Callbacks.html#gabd05790b93ac3ef7d7e91e9d8bc46295).
 pub struct OnChangeThread {
@@ -26,13 +13,12 @@ pub struct OnChangeThread {
 
 impl OnChangeThread {
     /// Spawn the thread.
+    ///
+    /// The thread supervises its worker loop: if `on_change` panics, the panic
+    /// is logged and the worker is restarted to process the next event.
     pub fn spawn<F: Fn(OnChangeEvent) + Send + 'static>(on_change: F) -> Self {
         let (sender, rx) = std::sync::mpsc::channel();
-        let handle = std::thread::spawn(move || {
-            while let Ok(event) = rx.recv() {
-                on_change(event);
-            }
-        });
+        let handle = std::thread::spawn(move || supervise(rx, on_change));
         Self { sender, handle }
     }
 
@@ -47,3 +33,21 @@ impl OnChangeThread {
         self.handle.join()
     }
 }
+
+/// Call `on_change` for every event received, restarting whenever it panics.
+pub(super) fn supervise<F: Fn(OnChangeEvent)>(rx: Receiver<OnChangeEvent>, on_change: F) {
+    loop {
+        let worker = catch_unwind(AssertUnwindSafe(|| {
+            while let Ok(event) = rx.recv() {
+                on_change(event);
+            }
+        }));
+        match worker {
+            Ok(()) => break,
+            Err(payload) => tracing::error!(
+                panic = panic_message(payload.as_ref()),
+                "on change handler panicked, restarting worker"
+            ),
+        }
+    }
+}
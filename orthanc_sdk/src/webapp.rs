@@ -4,7 +4,8 @@ use std::ffi::{CStr, CString};
 
 use http::StatusCode;
 
-use crate::error_code::*;
+use crate::error_code::OrthancErrorCode;
+use crate::panic::catch_panic;
 use crate::sdk::{answer_buffer, set_http_header};
 use crate::send_http_status_code;
 use crate::{bindings, http::Method, send_method_not_allowed};
@@ -22,6 +23,7 @@ use crate::{bindings, http::Method, send_method_not_allowed};
 /// ## Behavior
 ///
 /// - The paths "" and "/" are mapped to `index.html`.
+/// - A panic is caught and reported as `OrthancPluginErrorCode_InternalError`.
 /// - Client-side routing is not supported (but should be easy to implement,
 ///   please open a feature or pull request on [GitHub](https://github.com/FNNDSC/orthanc-rs/).)
 ///
@@ -110,7 +112,12 @@ pub fn serve_static_file(
     request: *const bindings::OrthancPluginHttpRequest,
     bundle: &impl OrthancServableBundle,
 ) -> bindings::OrthancPluginErrorCode {
-    serve_static_file_impl(context, output, request, bundle).into_code()
+    catch_panic(
+        || match serve_static_file_impl(context, output, request, bundle) {
+            Ok(()) => OrthancErrorCode::Success.into(),
+            Err(code) => code.into(),
+        },
+    )
 }
 
 fn serve_static_file_impl(
@@ -118,46 +125,42 @@ fn serve_static_file_impl(
     output: *mut bindings::OrthancPluginRestOutput,
     request: *const bindings::OrthancPluginHttpRequest,
     bundle: &impl OrthancServableBundle,
-) -> ErrorCodeResult {
+) -> Result<(), OrthancErrorCode> {
     if !method_is_get(request) {
-        return send_method_not_allowed(context, output, c"GET").into_result();
+        return into_result(send_method_not_allowed(context, output, c"GET"));
     }
-    let path = unsafe { first_group_of(request) }.ok_or_else(|| send_not_found(context, output))?;
+    let Some(path) = (unsafe { first_group_of(request) }) else {
+        return into_result(send_not_found(context, output));
+    };
     // NOTE: Orthanc strips hash `#` and query `?` components from the URI for us
     let resolved_path = if path.is_empty() { "index.html" } else { path };
-    let code = if let Some(file) = bundle.get_file(resolved_path) {
-        if file.is_immutable() {
-            set_http_header(
-                context,
-                output,
-                c"Cache-Control",
-                c"public, max-age=31536000, immutable",
-            )
-            .into_result()?;
-        }
-        if let Some(date) = file.last_modified() {
-            set_http_header(context, output, c"Last-Modified", date).into_result()?;
-        }
-        if let Some(etag) = file.etag() {
-            if let Some(value) = unsafe { get_header(request, c"if-none-match") }
-                && value == etag
-            {
-                send_not_modified(context, output).into_result()
-            } else {
-                set_http_header(context, output, c"ETag", etag)
-                    .into_result()
-                    .and_then(|_| {
-                        answer_buffer(context, output, file.body(), file.mime()).into_result()
-                    })
-            }
-        } else {
-            answer_buffer(context, output, file.body(), file.mime()).into_result()
-        }
-        .into_code()
-    } else {
-        send_not_found(context, output)
+    let Some(file) = bundle.get_file(resolved_path) else {
+        return into_result(send_not_found(context, output));
     };
-    Ok(code)
+    if file.is_immutable() {
+        into_result(set_http_header(
+            context,
+            output,
+            c"Cache-Control",
+            c"public, max-age=31536000, immutable",
+        ))?;
+    }
+    if let Some(date) = file.last_modified() {
+        into_result(set_http_header(context, output, c"Last-Modified", date))?;
+    }
+    if let Some(etag) = file.etag() {
+        if let Some(value) = unsafe { get_header(request, c"if-none-match") }
+            && value == etag
+        {
+            return into_result(send_not_modified(context, output));
+        }
+        into_result(set_http_header(context, output, c"ETag", etag))?;
+    }
+    into_result(answer_buffer(context, output, file.body(), file.mime()))
+}
+
+fn into_result(code: bindings::OrthancPluginErrorCode) -> Result<(), OrthancErrorCode> {
+    OrthancErrorCode::from(code).into_result()
 }
 
 fn send_not_found(